@@ -77,6 +77,10 @@ struct ChatMessage: Identifiable, Hashable, Codable {
     var toolCallID: String?          // When role == .tool, the ID of the tool call this responds to
     var toolName: String?            // When role == .tool, the name of the tool
 
+    // Reproducibility details, for assistant replies requested with a seed
+    var seed: Int?
+    var systemFingerprint: String?
+
     init(id: UUID, role: Role, text: String, timestamp: Date, attachments: [Attachment] = [],
          toolCalls: [ToolCall]? = nil, toolCallID: String? = nil, toolName: String? = nil) {
         self.id = id
@@ -36,6 +36,43 @@ enum AIProvider: String, CaseIterable, Identifiable, Hashable {
         }
     }
 
+    /// Sampling temperatures the provider accepts. Values outside are clamped before sending.
+    var temperatureRange: ClosedRange<Double> {
+        switch self {
+        case .anthropic, .kimi: return 0...1
+        default: return 0...2
+        }
+    }
+
+    /// Whether requests to this provider accept a sampling `seed`.
+    var supportsSeed: Bool {
+        switch self {
+        case .anthropic, .kimi: return false
+        default: return true
+        }
+    }
+
+    /// Whether the provider has native web search for the given model.
+    /// Gemini rejects Google Search combined with function declarations, so it only
+    /// qualifies when the request carries no tools.
+    func supportsWebSearch(modelID: String, sendsFunctionTools: Bool) -> Bool {
+        switch self {
+        case .openAI: return modelID.contains("search")
+        case .anthropic, .openRouter: return true
+        case .gemini: return !sendsFunctionTools
+        default: return false
+        }
+    }
+
+    /// Documented maximum request body size, when the provider publishes one.
+    var maxRequestBodyBytes: Int? {
+        switch self {
+        case .anthropic: return 32 * 1024 * 1024
+        case .gemini: return 20 * 1024 * 1024
+        default: return nil
+        }
+    }
+
     /// Whether this provider requires a traditional API key.
     var requiresAPIKey: Bool {
         switch self {
@@ -219,16 +256,110 @@ struct TokenUsage: Hashable, Sendable {
     var remainingTokens: Int { max(0, totalTokens - outputTokens) }
 }
 
+/// A web source cited by a provider's native search.
+struct WebCitation: Hashable, Sendable {
+    let url: String
+    let title: String?
+}
+
+/// Why a response was cut off client-side.
+enum StreamTruncation {
+    /// The estimated output passed the requested max tokens.
+    case outputTokenLimit(Int)
+    /// The response passed the fixed size cap.
+    case responseSize
+}
+
 /// The result of a completed streaming response.
 struct StreamResult {
     let text: String
     let toolCalls: [ToolCallInfo]
     let usage: TokenUsage?
+    let citations: [WebCitation]
+    /// Set when the stream was cut off at the output token limit or the response size cap.
+    let truncation: StreamTruncation?
+    /// Backend fingerprint reported alongside seeded responses (OpenAI-format only).
+    let systemFingerprint: String?
+    /// Why the provider refused or safety-blocked the response, if it did.
+    let refusal: String?
 
-    init(text: String, toolCalls: [ToolCallInfo] = [], usage: TokenUsage? = nil) {
+    init(
+        text: String, toolCalls: [ToolCallInfo] = [], usage: TokenUsage? = nil,
+        citations: [WebCitation] = [], truncation: StreamTruncation? = nil,
+        systemFingerprint: String? = nil, refusal: String? = nil
+    ) {
         self.text = text
         self.toolCalls = toolCalls
         self.usage = usage
+        self.citations = citations
+        self.truncation = truncation
+        self.systemFingerprint = systemFingerprint
+        self.refusal = refusal
+    }
+}
+
+/// Per-request options applied on top of the conversation history.
+struct LLMRequestOptions: Hashable, Sendable {
+    /// Ask the provider to ground the answer with its native web search, when supported.
+    var isWebSearchEnabled: Bool
+    /// Sampling temperature; nil leaves the provider default.
+    var temperature: Double?
+    /// Maximum output tokens; nil leaves the adapter default.
+    var maxTokens: Int?
+    /// Stable identifier of the conversation, used to keep it on one endpoint.
+    var conversationID: String?
+    /// Sampling seed for reproducible output, where the provider supports it.
+    var seed: Int?
+
+    init(
+        isWebSearchEnabled: Bool = false, temperature: Double? = nil, maxTokens: Int? = nil,
+        conversationID: String? = nil, seed: Int? = nil
+    ) {
+        self.isWebSearchEnabled = isWebSearchEnabled
+        self.temperature = temperature
+        self.maxTokens = maxTokens
+        self.conversationID = conversationID
+        self.seed = seed
+    }
+}
+
+extension LLMRequestOptions {
+    /// Names of the options set here that the provider can't honor for this model.
+    func unsupportedParameters(
+        for provider: AIProvider, modelID: String, sendsFunctionTools: Bool
+    ) -> [String] {
+        var names: [String] = []
+        if seed != nil && !provider.supportsSeed {
+            names.append("seed")
+        }
+        if isWebSearchEnabled
+            && !provider.supportsWebSearch(modelID: modelID, sendsFunctionTools: sendsFunctionTools)
+        {
+            names.append("web search")
+        }
+        return names
+    }
+}
+
+/// What to do when a request sets options the target provider can't honor.
+enum UnsupportedParameterPolicy: String, CaseIterable, Identifiable {
+    /// Drop the options without telling the user.
+    case strip
+    /// Drop the options and show a status warning.
+    case warn
+    /// Refuse to send the request.
+    case reject
+
+    static let storageKey = "unsupported_parameter_policy"
+
+    var id: String { rawValue }
+
+    var title: String {
+        switch self {
+        case .strip: return "Ignore"
+        case .warn: return "Warn"
+        case .reject: return "Block"
+        }
     }
 }
 
@@ -243,6 +374,7 @@ protocol LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult
 }
@@ -251,6 +383,28 @@ enum AdapterError: LocalizedError {
     case invalidResponse
     case missingResponseText
     case api(message: String)
+    case rateLimited(message: String?)
+    case contextLengthExceeded(message: String?)
+    case authentication(message: String?)
+    case permissionDenied(message: String?)
+    case providerUnavailable(message: String?)
+    /// Size and limit are known when caught before sending; a server 413 has only a message.
+    case requestTooLarge(size: Int?, limit: Int?, message: String? = nil)
+
+    /// Stable identifier for branching on the failure kind without parsing messages.
+    var code: String {
+        switch self {
+        case .invalidResponse: return "invalid_response"
+        case .missingResponseText: return "empty_response"
+        case .api: return "api_error"
+        case .rateLimited: return "rate_limited"
+        case .contextLengthExceeded: return "context_length_exceeded"
+        case .authentication: return "authentication_error"
+        case .permissionDenied: return "permission_denied"
+        case .providerUnavailable: return "provider_unavailable"
+        case .requestTooLarge: return "request_too_large"
+        }
+    }
 
     var errorDescription: String? {
         switch self {
@@ -260,6 +414,32 @@ enum AdapterError: LocalizedError {
             return "The model returned an empty response."
         case .api(let message):
             return message
+        case .rateLimited(let message):
+            return Self.describe("The provider is rate limiting requests.", detail: message)
+        case .contextLengthExceeded(let message):
+            return Self.describe(
+                "The conversation is too long for this model's context window.", detail: message)
+        case .authentication(let message):
+            return Self.describe("The provider rejected the API key.", detail: message)
+        case .permissionDenied(let message):
+            return Self.describe(
+                "The provider denied access. The API key may not have access to this model or region.",
+                detail: message)
+        case .providerUnavailable(let message):
+            return Self.describe("The provider is temporarily unavailable.", detail: message)
+        case .requestTooLarge(let size?, let limit?, _):
+            let formatter = ByteCountFormatter()
+            return
+                "The request is \(formatter.string(fromByteCount: Int64(size))), over the provider's \(formatter.string(fromByteCount: Int64(limit))) limit. Remove some attachments or start a new chat."
+        case .requestTooLarge(_, _, let message):
+            return Self.describe(
+                "The request is too large for the provider. Remove some attachments or start a new chat.",
+                detail: message)
         }
     }
+
+    private static func describe(_ summary: String, detail: String?) -> String {
+        guard let detail, !detail.isEmpty else { return summary }
+        return "\(summary) \(detail)"
+    }
 }
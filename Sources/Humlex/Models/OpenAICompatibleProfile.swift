@@ -4,10 +4,42 @@ struct OpenAICompatibleProfile: Identifiable, Codable, Hashable {
     let id: String
     var name: String
     var baseURL: String
+    /// Extra endpoints serving the same models (e.g. other regions), used for failover.
+    var fallbackBaseURLs: [String]?
+    /// PEM bundle or DER file with extra CA roots for endpoints behind private PKI.
+    var caCertificatePath: String?
+    /// PKCS#12 client identity presented for mutual TLS.
+    var clientCertificatePath: String?
 
-    init(id: String = UUID().uuidString, name: String, baseURL: String) {
+    init(
+        id: String = UUID().uuidString, name: String, baseURL: String,
+        fallbackBaseURLs: [String]? = nil,
+        caCertificatePath: String? = nil, clientCertificatePath: String? = nil
+    ) {
         self.id = id
         self.name = name
         self.baseURL = baseURL
+        self.fallbackBaseURLs = fallbackBaseURLs
+        self.caCertificatePath = caCertificatePath
+        self.clientCertificatePath = clientCertificatePath
+    }
+
+    /// Keychain account holding the client certificate password.
+    var clientCertificatePasswordKey: String {
+        Self.clientCertificatePasswordKey(for: id)
+    }
+
+    static func clientCertificatePasswordKey(for profileID: String) -> String {
+        "openai_compatible_client_cert_password_\(profileID)"
+    }
+
+    /// Load this profile's custom TLS settings (CA roots and client identity).
+    func loadTLSSettings() throws -> TLSSettings {
+        let password = (try? KeychainStore.loadString(for: clientCertificatePasswordKey)) ?? nil
+        return try TLSSettings.load(
+            caCertificatePath: caCertificatePath,
+            clientCertificatePath: clientCertificatePath,
+            clientCertificatePassword: password
+        )
     }
 }
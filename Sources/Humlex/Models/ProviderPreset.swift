@@ -0,0 +1,100 @@
+import Foundation
+
+/// Request defaults attached to a provider's API key.
+/// Merged into requests that don't specify their own values.
+struct ProviderPreset: Codable, Hashable {
+    var systemPrompt: String
+    var temperature: Double?
+    var maxTokens: Int?
+    var seed: Int?
+    /// Comma-separated error codes or message fragments that are safe to retry,
+    /// e.g. "overloaded, provider_unavailable".
+    var retryErrorPatterns: String
+    /// When set, the preset prompt is always sent first and chat-level
+    /// instructions are demoted below it instead of replacing it.
+    var isSystemPromptPinned: Bool
+
+    init(
+        systemPrompt: String = "", temperature: Double? = nil, maxTokens: Int? = nil,
+        seed: Int? = nil, retryErrorPatterns: String = "", isSystemPromptPinned: Bool = false
+    ) {
+        self.systemPrompt = systemPrompt
+        self.temperature = temperature
+        self.maxTokens = maxTokens
+        self.seed = seed
+        self.retryErrorPatterns = retryErrorPatterns
+        self.isSystemPromptPinned = isSystemPromptPinned
+    }
+
+    // Custom Codable to handle missing keys from older saved presets
+    enum CodingKeys: String, CodingKey {
+        case systemPrompt, temperature, maxTokens, seed, retryErrorPatterns, isSystemPromptPinned
+    }
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        systemPrompt = try container.decodeIfPresent(String.self, forKey: .systemPrompt) ?? ""
+        temperature = try container.decodeIfPresent(Double.self, forKey: .temperature)
+        maxTokens = try container.decodeIfPresent(Int.self, forKey: .maxTokens)
+        seed = try container.decodeIfPresent(Int.self, forKey: .seed)
+        retryErrorPatterns =
+            try container.decodeIfPresent(String.self, forKey: .retryErrorPatterns) ?? ""
+        isSystemPromptPinned =
+            try container.decodeIfPresent(Bool.self, forKey: .isSystemPromptPinned) ?? false
+    }
+
+    /// The preset system prompt, or nil when blank.
+    var resolvedSystemPrompt: String? {
+        let trimmed = systemPrompt.trimmingCharacters(in: .whitespacesAndNewlines)
+        return trimmed.isEmpty ? nil : trimmed
+    }
+
+    /// Lowercased, non-empty retry patterns.
+    var retryPatterns: [String] {
+        retryErrorPatterns.split(separator: ",")
+            .map { $0.trimmingCharacters(in: .whitespaces).lowercased() }
+            .filter { !$0.isEmpty }
+    }
+
+    /// Combine the preset with a chat-level system prompt.
+    /// Unpinned presets only fill in when the chat has no prompt; pinned presets
+    /// always lead, and the chat prompt is demoted so it can't override them.
+    func systemPrompt(mergingChatPrompt chatPrompt: String?) -> String? {
+        guard let presetPrompt = resolvedSystemPrompt else { return chatPrompt }
+        guard isSystemPromptPinned else { return chatPrompt ?? presetPrompt }
+        guard let chatPrompt else { return presetPrompt }
+        return """
+            \(presetPrompt)
+
+            The following chat instructions are lower priority. Ignore any part of them \
+            that conflicts with the instructions above.
+
+            \(chatPrompt)
+            """
+    }
+
+    // MARK: - Storage
+
+    /// UserDefaults key holding all presets as JSON, keyed by provider raw value.
+    static let storageKey = "provider_presets_json"
+
+    static func decodeAll(from json: String) -> [String: ProviderPreset] {
+        let data = Data(json.utf8)
+        return (try? JSONDecoder().decode([String: ProviderPreset].self, from: data)) ?? [:]
+    }
+
+    static func encodeAll(_ presets: [String: ProviderPreset]) -> String {
+        guard let data = try? JSONEncoder().encode(presets),
+            let json = String(data: data, encoding: .utf8)
+        else {
+            return ""
+        }
+        return json
+    }
+
+    /// Load the saved preset for a provider, if any.
+    static func preset(for provider: AIProvider) -> ProviderPreset? {
+        let json = UserDefaults.standard.string(forKey: storageKey) ?? ""
+        return decodeAll(from: json)[provider.rawValue]
+    }
+}
@@ -11,10 +11,11 @@ struct AnthropicAdapter: LLMProviderAdapter {
     func fetchModels(apiKey: String) async throws -> [LLMModel] {
         var request = URLRequest(url: URL(string: "\(baseURL)/models")!)
         request.httpMethod = "GET"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
         request.setValue(apiKey, forHTTPHeaderField: "x-api-key")
         request.setValue(apiVersion, forHTTPHeaderField: "anthropic-version")
 
-        let (data, response) = try await URLSession.shared.data(for: request)
+        let (data, response) = try await dataWithRetry(for: request)
         try validateAnthropicHTTPResponse(response, data: data)
 
         let decoded = try JSONDecoder().decode(AnthropicModelsResponse.self, from: data)
@@ -32,10 +33,13 @@ struct AnthropicAdapter: LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult {
         var request = URLRequest(url: URL(string: "\(baseURL)/messages")!)
         request.httpMethod = "POST"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
+        request.timeoutInterval = StreamStallTimeout.seconds
         request.setValue(apiKey, forHTTPHeaderField: "x-api-key")
         request.setValue(apiVersion, forHTTPHeaderField: "anthropic-version")
         request.setValue("application/json", forHTTPHeaderField: "Content-Type")
@@ -44,19 +48,27 @@ struct AnthropicAdapter: LLMProviderAdapter {
         let systemMessage = history.first { $0.role == .system }?.content
         let conversationHistory = history.filter { $0.role != .system }
 
-        let toolDefs = anthropicToolDefs(from: tools)
+        var toolDefs = anthropicToolDefs(from: tools) ?? []
+        if options.isWebSearchEnabled {
+            toolDefs.append(.webSearch(maxUses: 5))
+        }
         let body = AnthropicMessagesRequest(
             model: modelID,
-            max_tokens: 8192,
+            max_tokens: options.maxTokens ?? 8192,
+            temperature: options.temperature,
             system: systemMessage,
             stream: true,
             messages: conversationHistory.map { anthropicMessage(from: $0) },
-            tools: toolDefs
+            tools: toolDefs.isEmpty ? nil : toolDefs
         )
-        request.httpBody = try JSONEncoder().encode(body)
+        let bodyData = try JSONEncoder().encode(body)
+        try validateRequestBodySize(bodyData, for: provider)
+        request.httpBody = bodyData
 
         let (bytes, response) = try await URLSession.shared.bytes(for: request)
-        return try await streamAnthropicSSE(bytes: bytes, response: response, onEvent: onEvent)
+        return try await streamAnthropicSSE(
+            bytes: bytes, response: response, outputTokenLimit: options.maxTokens,
+            onEvent: onEvent)
     }
 }
 
@@ -131,6 +143,7 @@ private func anthropicMessage(from msg: LLMChatMessage) -> AnthropicMessagesRequ
 private func streamAnthropicSSE(
     bytes: URLSession.AsyncBytes,
     response: URLResponse,
+    outputTokenLimit: Int?,
     onEvent: @escaping @Sendable (StreamEvent) async -> Void
 ) async throws -> StreamResult {
     guard let http = response as? HTTPURLResponse else {
@@ -139,10 +152,8 @@ private func streamAnthropicSSE(
 
     if !(200...299).contains(http.statusCode) {
         let data = try await collectData(from: bytes)
-        if let apiError = try? JSONDecoder().decode(AnthropicErrorEnvelope.self, from: data) {
-            throw AdapterError.api(message: apiError.error.message)
-        }
-        throw AdapterError.api(message: "Request failed with status \(http.statusCode).")
+        let apiError = try? JSONDecoder().decode(AnthropicErrorEnvelope.self, from: data)
+        throw AdapterError.classify(status: http.statusCode, message: apiError?.error.message)
     }
 
     var emittedAny = false
@@ -151,19 +162,30 @@ private func streamAnthropicSSE(
     // Track tool use blocks being assembled
     var currentToolUseIndex = 0
     var toolUseAccumulators: [Int: (id: String, name: String, arguments: String)] = [:]
-    // Track usage from the final message (if present)
-    var finalUsage: TokenUsage?
-
-    for try await line in bytes.lines {
+    // Input tokens arrive in message_start, cumulative output tokens in message_delta
+    var inputTokens: Int?
+    var outputTokens: Int?
+    // Track citations from the server-side web search tool
+    var citations: [WebCitation] = []
+    var outputBudget = OutputTokenBudget(limit: outputTokenLimit)
+    var truncation: StreamTruncation?
+    var refusal: String?
+
+    streamLoop: for try await sseEvent in ServerSentEvents(bytes: bytes) {
         try Task.checkCancellation()
 
-        guard line.hasPrefix("data:") else { continue }
-        let payload = String(line.dropFirst(5)).trimmingCharacters(in: .whitespaces)
+        let payload = sseEvent.data.trimmingCharacters(in: .whitespaces)
 
         guard !payload.isEmpty, let data = payload.data(using: .utf8) else { continue }
 
         if let event = try? decoder.decode(AnthropicStreamEvent.self, from: data) {
             switch event.type {
+            case "message_start":
+                if let usage = event.message?.usage {
+                    inputTokens = usage.inputTokens ?? inputTokens
+                    outputTokens = usage.outputTokens ?? outputTokens
+                }
+
             case "content_block_start":
                 if let contentBlock = event.contentBlock {
                     if contentBlock.type == "tool_use" {
@@ -184,27 +206,43 @@ private func streamAnthropicSSE(
                         emittedAny = true
                         fullText += text
                         await onEvent(.textDelta(text))
+                        if let reason = outputBudget.consume(text) {
+                            truncation = reason
+                            bytes.task.cancel()
+                            break streamLoop
+                        }
                     } else if delta.type == "input_json_delta", let partial = delta.partialJson,
-                        !partial.isEmpty
+                        !partial.isEmpty,
+                        toolUseAccumulators[currentToolUseIndex] != nil
                     {
+                        // server_tool_use blocks (web search) also stream input JSON,
+                        // but they run on Anthropic's side and have no accumulator.
                         toolUseAccumulators[currentToolUseIndex]?.arguments += partial
                         await onEvent(
                             .toolCallArgumentDelta(index: currentToolUseIndex, delta: partial))
                         emittedAny = true
+                        if let reason = outputBudget.consume(partial) {
+                            truncation = reason
+                            bytes.task.cancel()
+                            break streamLoop
+                        }
+                    } else if delta.type == "citations_delta", let citation = delta.citation,
+                        let url = citation.url
+                    {
+                        citations.append(WebCitation(url: url, title: citation.title))
                     }
                 }
 
             case "content_block_stop":
                 currentToolUseIndex += 1
 
-            case "message_stop":
-                // Message complete - check for usage if available
+            case "message_delta":
                 if let usage = event.usage {
-                    finalUsage = TokenUsage(
-                        inputTokens: usage.inputTokens,
-                        outputTokens: usage.outputTokens,
-                        totalTokens: usage.inputTokens + usage.outputTokens
-                    )
+                    inputTokens = usage.inputTokens ?? inputTokens
+                    outputTokens = usage.outputTokens ?? outputTokens
+                }
+                if event.delta?.stopReason == "refusal" {
+                    refusal = "The model declined to continue this response."
                 }
 
             default:
@@ -214,30 +252,51 @@ private func streamAnthropicSSE(
         }
 
         if let apiError = try? decoder.decode(AnthropicErrorEnvelope.self, from: data) {
-            throw AdapterError.api(message: apiError.error.message)
+            throw AdapterError.classify(status: nil, message: apiError.error.message)
         }
     }
 
+    if let refusal {
+        await appendRefusalNotice(refusal, to: &fullText, onEvent: onEvent)
+        emittedAny = true
+    }
+
     if !emittedAny {
         throw AdapterError.missingResponseText
     }
 
-    let toolCalls = toolUseAccumulators.sorted(by: { $0.key < $1.key }).map { (_, acc) in
-        ToolCallInfo(id: acc.id, name: acc.name, arguments: acc.arguments, serverName: "")
+    var finalUsage: TokenUsage?
+    if let inputTokens, let outputTokens {
+        finalUsage = TokenUsage(
+            inputTokens: inputTokens,
+            outputTokens: outputTokens,
+            totalTokens: inputTokens + outputTokens
+        )
     }
 
+    let toolCalls =
+        truncation != nil
+        ? []
+        : toolUseAccumulators.sorted(by: { $0.key < $1.key }).map { (_, acc) in
+            ToolCallInfo(id: acc.id, name: acc.name, arguments: acc.arguments, serverName: "")
+        }
+
+    await appendCitationSources(citations, to: &fullText, onEvent: onEvent)
     await onEvent(.done)
-    return StreamResult(text: fullText, toolCalls: toolCalls, usage: finalUsage)
+    return StreamResult(
+        text: fullText, toolCalls: toolCalls, usage: finalUsage, citations: citations,
+        truncation: truncation, refusal: refusal)
 }
 
-private func anthropicToolDefs(from mcpTools: [MCPTool]) -> [AnthropicToolDefinition]? {
+private func anthropicToolDefs(from mcpTools: [MCPTool]) -> [AnthropicTool]? {
     guard !mcpTools.isEmpty else { return nil }
     return mcpTools.map { tool in
-        AnthropicToolDefinition(
-            name: tool.name,
-            description: tool.description,
-            input_schema: AnyCodable(tool.inputSchema.mapValues { $0.value })
-        )
+        .custom(
+            AnthropicToolDefinition(
+                name: tool.name,
+                description: tool.description,
+                input_schema: AnyCodable(tool.inputSchema.mapValues { $0.value })
+            ))
     }
 }
 
@@ -246,10 +305,8 @@ private func validateAnthropicHTTPResponse(_ response: URLResponse, data: Data)
         throw AdapterError.invalidResponse
     }
     guard (200...299).contains(http.statusCode) else {
-        if let apiError = try? JSONDecoder().decode(AnthropicErrorEnvelope.self, from: data) {
-            throw AdapterError.api(message: apiError.error.message)
-        }
-        throw AdapterError.api(message: "Request failed with status \(http.statusCode).")
+        let apiError = try? JSONDecoder().decode(AnthropicErrorEnvelope.self, from: data)
+        throw AdapterError.classify(status: http.statusCode, message: apiError?.error.message)
     }
 }
 
@@ -275,6 +332,29 @@ struct AnthropicToolDefinition: Encodable {
     let input_schema: AnyCodable
 }
 
+/// A client-defined tool or one of Anthropic's server tools.
+enum AnthropicTool: Encodable {
+    case custom(AnthropicToolDefinition)
+    case webSearch(maxUses: Int)
+
+    func encode(to encoder: Encoder) throws {
+        switch self {
+        case .custom(let definition):
+            try definition.encode(to: encoder)
+        case .webSearch(let maxUses):
+            var container = encoder.container(keyedBy: CodingKeys.self)
+            try container.encode("web_search_20250305", forKey: .type)
+            try container.encode("web_search", forKey: .name)
+            try container.encode(maxUses, forKey: .maxUses)
+        }
+    }
+
+    private enum CodingKeys: String, CodingKey {
+        case type, name
+        case maxUses = "max_uses"
+    }
+}
+
 struct AnthropicMessagesRequest: Encodable {
     struct Message: Encodable {
         enum Content: Encodable {
@@ -345,10 +425,11 @@ struct AnthropicMessagesRequest: Encodable {
 
     let model: String
     let max_tokens: Int
+    let temperature: Double?
     let system: String?
     let stream: Bool
     let messages: [Message]
-    let tools: [AnthropicToolDefinition]?
+    let tools: [AnthropicTool]?
 }
 
 struct AnthropicStreamEvent: Decodable {
@@ -356,27 +437,42 @@ struct AnthropicStreamEvent: Decodable {
     let delta: Delta?
     let contentBlock: ContentBlock?
     let usage: Usage?
+    let message: Message?
+
+    /// The message envelope sent with `message_start`.
+    struct Message: Decodable {
+        let usage: Usage?
+    }
 
     struct Delta: Decodable {
         let type: String?
         let text: String?
         let partialJson: String?
+        let citation: Citation?
+        let stopReason: String?
 
         enum CodingKeys: String, CodingKey {
-            case type, text
+            case type, text, citation
             case partialJson = "partial_json"
+            case stopReason = "stop_reason"
         }
     }
 
+    struct Citation: Decodable {
+        let url: String?
+        let title: String?
+    }
+
     struct ContentBlock: Decodable {
         let type: String
         let id: String?
         let name: String?
     }
 
+    /// `message_delta` usage may omit input tokens, so both are optional.
     struct Usage: Decodable {
-        let inputTokens: Int
-        let outputTokens: Int
+        let inputTokens: Int?
+        let outputTokens: Int?
 
         enum CodingKeys: String, CodingKey {
             case inputTokens = "input_tokens"
@@ -387,7 +483,7 @@ struct AnthropicStreamEvent: Decodable {
     enum CodingKeys: String, CodingKey {
         case type, delta
         case contentBlock = "content_block"
-        case usage
+        case usage, message
     }
 }
 
@@ -8,9 +8,10 @@ struct FastRouterAdapter: LLMProviderAdapter {
     func fetchModels(apiKey: String) async throws -> [LLMModel] {
         var request = URLRequest(url: URL(string: "\(baseURL)/models")!)
         request.httpMethod = "GET"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
         request.setValue("Bearer \(apiKey)", forHTTPHeaderField: "Authorization")
 
-        let (data, response) = try await URLSession.shared.data(for: request)
+        let (data, response) = try await dataWithRetry(for: request)
         try validateHTTPResponse(response, data: data)
 
         let decoded = try JSONDecoder().decode(FastRouterModelsResponse.self, from: data)
@@ -26,6 +27,7 @@ struct FastRouterAdapter: LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult {
         do {
@@ -34,6 +36,7 @@ struct FastRouterAdapter: LLMProviderAdapter {
                 modelID: modelID,
                 apiKey: apiKey,
                 tools: tools,
+                options: options,
                 onEvent: onEvent
             )
         } catch let error as AdapterError {
@@ -48,6 +51,7 @@ struct FastRouterAdapter: LLMProviderAdapter {
                     modelID: modelID,
                     apiKey: apiKey,
                     tools: [],
+                    options: options,
                     onEvent: onEvent
                 )
             }
@@ -60,10 +64,13 @@ struct FastRouterAdapter: LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult {
         var request = URLRequest(url: URL(string: "\(baseURL)/chat/completions")!)
         request.httpMethod = "POST"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
+        request.timeoutInterval = StreamStallTimeout.seconds
         request.setValue("Bearer \(apiKey)", forHTTPHeaderField: "Authorization")
         request.setValue("application/json", forHTTPHeaderField: "Content-Type")
 
@@ -71,12 +78,17 @@ struct FastRouterAdapter: LLMProviderAdapter {
             model: modelID,
             stream: true,
             messages: history.map { apiMessage(from: $0) },
-            tools: fastRouterToolDefs(from: tools)
+            tools: fastRouterToolDefs(from: tools),
+            temperature: options.temperature,
+            maxTokens: options.maxTokens,
+            seed: options.seed
         )
         request.httpBody = try JSONEncoder().encode(body)
 
         let (bytes, response) = try await URLSession.shared.bytes(for: request)
-        return try await streamSSE(bytes: bytes, response: response, onEvent: onEvent)
+        return try await streamSSE(
+            bytes: bytes, response: response, outputTokenLimit: options.maxTokens,
+            onEvent: onEvent)
     }
 }
 
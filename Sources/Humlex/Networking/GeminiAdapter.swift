@@ -10,8 +10,9 @@ struct GeminiAdapter: LLMProviderAdapter {
     func fetchModels(apiKey: String) async throws -> [LLMModel] {
         var request = URLRequest(url: URL(string: "\(baseURL)/models?key=\(apiKey)")!)
         request.httpMethod = "GET"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
 
-        let (data, response) = try await URLSession.shared.data(for: request)
+        let (data, response) = try await dataWithRetry(for: request)
         try validateGeminiHTTPResponse(response, data: data)
 
         let decoded = try JSONDecoder().decode(GeminiModelsResponse.self, from: data)
@@ -40,6 +41,7 @@ struct GeminiAdapter: LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult {
         let url = URL(
@@ -47,17 +49,33 @@ struct GeminiAdapter: LLMProviderAdapter {
         )!
         var request = URLRequest(url: url)
         request.httpMethod = "POST"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
+        request.timeoutInterval = StreamStallTimeout.seconds
         request.setValue("application/json", forHTTPHeaderField: "Content-Type")
 
-        let geminiTools = geminiToolDefs(from: tools)
+        var geminiTools = geminiToolDefs(from: tools) ?? []
+        // Google Search can't be combined with function declarations
+        if options.isWebSearchEnabled && geminiTools.isEmpty {
+            geminiTools.append(GeminiStreamRequest.Tool(googleSearch: .init()))
+        }
         let body = GeminiStreamRequest(
             contents: buildGeminiContents(from: history),
-            tools: geminiTools
+            tools: geminiTools.isEmpty ? nil : geminiTools,
+            generationConfig: options.temperature == nil && options.maxTokens == nil
+                && options.seed == nil
+                ? nil
+                : .init(
+                    temperature: options.temperature, maxOutputTokens: options.maxTokens,
+                    seed: options.seed)
         )
-        request.httpBody = try JSONEncoder().encode(body)
+        let bodyData = try JSONEncoder().encode(body)
+        try validateRequestBodySize(bodyData, for: provider)
+        request.httpBody = bodyData
 
         let (bytes, response) = try await URLSession.shared.bytes(for: request)
-        return try await streamGeminiSSE(bytes: bytes, response: response, onEvent: onEvent)
+        return try await streamGeminiSSE(
+            bytes: bytes, response: response, outputTokenLimit: options.maxTokens,
+            onEvent: onEvent)
     }
 }
 
@@ -251,6 +269,7 @@ private func geminiContent(from msg: LLMChatMessage) -> GeminiStreamRequest.Cont
 private func streamGeminiSSE(
     bytes: URLSession.AsyncBytes,
     response: URLResponse,
+    outputTokenLimit: Int?,
     onEvent: @escaping @Sendable (StreamEvent) async -> Void
 ) async throws -> StreamResult {
     guard let http = response as? HTTPURLResponse else {
@@ -259,10 +278,8 @@ private func streamGeminiSSE(
 
     if !(200...299).contains(http.statusCode) {
         let data = try await collectData(from: bytes)
-        if let apiError = try? JSONDecoder().decode(GeminiErrorEnvelope.self, from: data) {
-            throw AdapterError.api(message: apiError.error.message)
-        }
-        throw AdapterError.api(message: "Request failed with status \(http.statusCode).")
+        let apiError = try? JSONDecoder().decode(GeminiErrorEnvelope.self, from: data)
+        throw AdapterError.classify(status: http.statusCode, message: apiError?.error.message)
     }
 
     var emittedAny = false
@@ -270,23 +287,45 @@ private func streamGeminiSSE(
     var fullText = ""
     var toolCalls: [ToolCallInfo] = []
     var toolCallIndex = 0
+    // Track grounding sources from Google Search
+    var citations: [WebCitation] = []
+    var outputBudget = OutputTokenBudget(limit: outputTokenLimit)
+    var truncation: StreamTruncation?
+    var refusal: String?
 
-    for try await line in bytes.lines {
+    streamLoop: for try await sseEvent in ServerSentEvents(bytes: bytes) {
         try Task.checkCancellation()
 
-        guard line.hasPrefix("data:") else { continue }
-        let payload = String(line.dropFirst(5)).trimmingCharacters(in: .whitespaces)
+        let payload = sseEvent.data.trimmingCharacters(in: .whitespaces)
 
         if payload == "[DONE]" { break }
         guard let data = payload.data(using: .utf8) else { continue }
 
         if let chunk = try? decoder.decode(GeminiStreamChunk.self, from: data) {
+            if let blockReason = chunk.promptFeedback?.blockReason {
+                refusal = "The prompt was blocked by Gemini's safety filters (\(blockReason))."
+            }
             for candidate in chunk.candidates ?? [] {
+                if let reason = candidate.finishReason,
+                    GeminiStreamChunk.blockingFinishReasons.contains(reason)
+                {
+                    refusal = "The response was blocked by Gemini's safety filters (\(reason))."
+                }
+                for groundingChunk in candidate.groundingMetadata?.groundingChunks ?? [] {
+                    if let web = groundingChunk.web, let uri = web.uri {
+                        citations.append(WebCitation(url: uri, title: web.title))
+                    }
+                }
                 for part in candidate.content?.parts ?? [] {
                     if let text = part.text, !text.isEmpty {
                         emittedAny = true
                         fullText += text
                         await onEvent(.textDelta(text))
+                        if let reason = outputBudget.consume(text) {
+                            truncation = reason
+                            bytes.task.cancel()
+                            break streamLoop
+                        }
                     }
                     if let fc = part.functionCall {
                         let id = "gemini-tc-\(toolCallIndex)"
@@ -306,6 +345,11 @@ private func streamGeminiSSE(
                             .toolCallArgumentDelta(index: toolCallIndex, delta: argsString))
                         toolCallIndex += 1
                         emittedAny = true
+                        if let reason = outputBudget.consume(argsString) {
+                            truncation = reason
+                            bytes.task.cancel()
+                            break streamLoop
+                        }
                     }
                 }
             }
@@ -313,16 +357,24 @@ private func streamGeminiSSE(
         }
 
         if let apiError = try? decoder.decode(GeminiErrorEnvelope.self, from: data) {
-            throw AdapterError.api(message: apiError.error.message)
+            throw AdapterError.classify(status: nil, message: apiError.error.message)
         }
     }
 
+    if let refusal {
+        await appendRefusalNotice(refusal, to: &fullText, onEvent: onEvent)
+        emittedAny = true
+    }
+
     if !emittedAny {
         throw AdapterError.missingResponseText
     }
 
+    await appendCitationSources(citations, to: &fullText, onEvent: onEvent)
     await onEvent(.done)
-    return StreamResult(text: fullText, toolCalls: toolCalls, usage: nil)
+    return StreamResult(
+        text: fullText, toolCalls: truncation != nil ? [] : toolCalls, usage: nil,
+        citations: citations, truncation: truncation, refusal: refusal)
 }
 
 private func geminiToolDefs(from mcpTools: [MCPTool]) -> [GeminiStreamRequest.Tool]? {
@@ -369,10 +421,8 @@ private func validateGeminiHTTPResponse(_ response: URLResponse, data: Data) thr
         throw AdapterError.invalidResponse
     }
     guard (200...299).contains(http.statusCode) else {
-        if let apiError = try? JSONDecoder().decode(GeminiErrorEnvelope.self, from: data) {
-            throw AdapterError.api(message: apiError.error.message)
-        }
-        throw AdapterError.api(message: "Request failed with status \(http.statusCode).")
+        let apiError = try? JSONDecoder().decode(GeminiErrorEnvelope.self, from: data)
+        throw AdapterError.classify(status: http.statusCode, message: apiError?.error.message)
     }
 }
 
@@ -446,15 +496,32 @@ struct GeminiStreamRequest: Encodable {
             let parameters: AnyCodable
         }
 
-        let functionDeclarations: [FunctionDeclaration]
+        /// Enables grounding with Google Search; encodes as an empty object.
+        struct GoogleSearch: Encodable {}
+
+        let functionDeclarations: [FunctionDeclaration]?
+        let googleSearch: GoogleSearch?
+
+        init(functionDeclarations: [FunctionDeclaration]? = nil, googleSearch: GoogleSearch? = nil) {
+            self.functionDeclarations = functionDeclarations
+            self.googleSearch = googleSearch
+        }
+    }
+
+    struct GenerationConfig: Encodable {
+        let temperature: Double?
+        let maxOutputTokens: Int?
+        let seed: Int?
     }
 
     let contents: [Content]
     let tools: [Tool]?
+    let generationConfig: GenerationConfig?
 
-    init(contents: [Content], tools: [Tool]? = nil) {
+    init(contents: [Content], tools: [Tool]? = nil, generationConfig: GenerationConfig? = nil) {
         self.contents = contents
         self.tools = tools
+        self.generationConfig = generationConfig
     }
 }
 
@@ -487,10 +554,35 @@ struct GeminiStreamChunk: Decodable {
             let parts: [Part]?
         }
 
+        struct GroundingMetadata: Decodable {
+            struct GroundingChunk: Decodable {
+                struct Web: Decodable {
+                    let uri: String?
+                    let title: String?
+                }
+
+                let web: Web?
+            }
+
+            let groundingChunks: [GroundingChunk]?
+        }
+
         let content: Content?
+        let groundingMetadata: GroundingMetadata?
+        let finishReason: String?
     }
 
+    struct PromptFeedback: Decodable {
+        let blockReason: String?
+    }
+
+    /// Finish reasons meaning the output was withheld by a safety or policy filter.
+    static let blockingFinishReasons: Set<String> = [
+        "SAFETY", "RECITATION", "BLOCKLIST", "PROHIBITED_CONTENT", "SPII", "IMAGE_SAFETY",
+    ]
+
     let candidates: [Candidate]?
+    let promptFeedback: PromptFeedback?
 }
 
 struct GeminiFunctionCall: Decodable {
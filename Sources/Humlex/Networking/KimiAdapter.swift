@@ -14,7 +14,7 @@ struct KimiAdapter: LLMProviderAdapter {
         request.setValue(userAgent, forHTTPHeaderField: "User-Agent")
 
         do {
-            let (data, response) = try await URLSession.shared.data(for: request)
+            let (data, response) = try await dataWithRetry(for: request)
             try validateHTTPResponse(response, data: data)
 
             let decoded = try JSONDecoder().decode(OpenAIModelsResponse.self, from: data)
@@ -42,10 +42,12 @@ struct KimiAdapter: LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult {
         var request = URLRequest(url: URL(string: "\(baseURL)/chat/completions")!)
         request.httpMethod = "POST"
+        request.timeoutInterval = StreamStallTimeout.seconds
         request.setValue("Bearer \(apiKey)", forHTTPHeaderField: "Authorization")
         request.setValue("application/json", forHTTPHeaderField: "Content-Type")
         request.setValue(userAgent, forHTTPHeaderField: "User-Agent")
@@ -57,13 +59,16 @@ struct KimiAdapter: LLMProviderAdapter {
             stream: true,
             messages: history.map { kimiMessage(from: $0) },
             tools: toolDefs,
-            maxOutputTokens: 32_768,
+            maxOutputTokens: options.maxTokens ?? 32_768,
+            temperature: options.temperature,
             reasoningEffort: "medium"
         )
         request.httpBody = try JSONEncoder().encode(body)
 
         let (bytes, response) = try await URLSession.shared.bytes(for: request)
-        return try await streamSSE(bytes: bytes, response: response, onEvent: onEvent)
+        return try await streamSSE(
+            bytes: bytes, response: response, outputTokenLimit: options.maxTokens,
+            onEvent: onEvent)
     }
 }
 
@@ -89,6 +94,7 @@ private struct KimiChatStreamRequest: Encodable {
     let messages: [Message]
     let tools: [[String: AnyCodable]]?
     let maxOutputTokens: Int
+    let temperature: Double?
     let reasoningEffort: String?
 
     enum CodingKeys: String, CodingKey {
@@ -97,6 +103,7 @@ private struct KimiChatStreamRequest: Encodable {
         case messages
         case tools
         case maxOutputTokens = "max_tokens"
+        case temperature
         case reasoningEffort = "reasoning_effort"
     }
 }
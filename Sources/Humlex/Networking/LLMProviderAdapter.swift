@@ -8,15 +8,157 @@ func compareModels(_ lhs: LLMModel, _ rhs: LLMModel) -> Bool {
     return lhs.displayName.localizedCaseInsensitiveCompare(rhs.displayName) == .orderedAscending
 }
 
+/// Identity headers sent to providers. Each value can be overridden in Settings.
+enum RequestAttribution {
+    static let userAgentKey = "request_user_agent"
+    static let openRouterRefererKey = "openrouter_attribution_referer"
+    static let openRouterTitleKey = "openrouter_attribution_title"
+
+    /// `Humlex/<version>`, used when no custom User-Agent is set.
+    static var defaultUserAgent: String {
+        let version =
+            Bundle.main.object(forInfoDictionaryKey: "CFBundleShortVersionString") as? String
+            ?? "dev"
+        return "Humlex/\(version)"
+    }
+
+    static var userAgent: String { storedValue(userAgentKey) ?? defaultUserAgent }
+    static var openRouterReferer: String {
+        storedValue(openRouterRefererKey) ?? "https://localhost"
+    }
+    static var openRouterTitle: String { storedValue(openRouterTitleKey) ?? "Humlex" }
+
+    private static func storedValue(_ key: String) -> String? {
+        let value = (UserDefaults.standard.string(forKey: key) ?? "")
+            .trimmingCharacters(in: .whitespacesAndNewlines)
+        return value.isEmpty ? nil : value
+    }
+}
+
+/// How long a streaming request may go without receiving any bytes before it's
+/// abandoned. Applied as the request's idle timeout, so slow but steady streams
+/// are never cut off.
+enum StreamStallTimeout {
+    static let storageKey = "stream_stall_timeout_seconds"
+    static let defaultSeconds: TimeInterval = 60
+
+    static var seconds: TimeInterval {
+        let stored = UserDefaults.standard.double(forKey: storageKey)
+        return stored > 0 ? stored : defaultSeconds
+    }
+}
+
 func validateHTTPResponse(_ response: URLResponse, data: Data) throws {
     guard let http = response as? HTTPURLResponse else {
         throw AdapterError.invalidResponse
     }
     guard (200...299).contains(http.statusCode) else {
-        if let apiError = try? JSONDecoder().decode(OpenAIErrorEnvelope.self, from: data) {
-            throw AdapterError.api(message: apiError.error.message)
+        let apiError = try? JSONDecoder().decode(OpenAIErrorEnvelope.self, from: data)
+        throw AdapterError.classify(status: http.statusCode, message: apiError?.error.message)
+    }
+}
+
+extension AdapterError {
+    /// Map a failed HTTP status and/or provider error message to a specific error.
+    /// In-stream error events have no status, so the message is checked as well.
+    static func classify(status: Int?, message: String?) -> AdapterError {
+        let lowered = (message ?? "").lowercased()
+        let isContextError = [
+            "context length", "context_length", "context window", "maximum context",
+            "prompt is too long", "too many tokens",
+        ].contains { lowered.contains($0) }
+
+        switch status {
+        case 401:
+            return .authentication(message: message)
+        case 403:
+            // Valid keys get 403 too, e.g. for model access or unsupported regions
+            return .permissionDenied(message: message)
+        case 429:
+            return .rateLimited(message: message)
+        case 413:
+            // Payload size, usually attachments, rather than the token count
+            return .requestTooLarge(size: nil, limit: nil, message: message)
+        case let code? where (500...599).contains(code):
+            return .providerUnavailable(message: message)
+        default:
+            break
+        }
+
+        if isContextError {
+            return .contextLengthExceeded(message: message)
+        }
+        if status == nil {
+            if lowered.contains("rate limit") { return .rateLimited(message: message) }
+            if lowered.contains("overloaded") { return .providerUnavailable(message: message) }
+        }
+        if let message, !message.isEmpty {
+            return .api(message: message)
         }
-        throw AdapterError.api(message: "Request failed with status \(http.statusCode).")
+        guard let status else { return .api(message: "The provider returned an error.") }
+        return .api(message: "Request failed with status \(status).")
+    }
+}
+
+/// Fail fast on bodies over the provider's documented size limit, instead of
+/// uploading them only to get an opaque 413 back.
+func validateRequestBodySize(_ body: Data, for provider: AIProvider) throws {
+    guard let limit = provider.maxRequestBodyBytes, body.count > limit else { return }
+    throw AdapterError.requestTooLarge(size: body.count, limit: limit)
+}
+
+/// Perform an idempotent request (model lists and other GETs), retrying transient
+/// transport failures such as DNS hiccups or dropped connections. Completions are
+/// never sent through this, since replaying them would bill twice.
+func dataWithRetry(
+    for request: URLRequest,
+    session: URLSession = .shared,
+    maxAttempts: Int = 3
+) async throws -> (Data, URLResponse) {
+    let transientCodes: Set<URLError.Code> = [
+        .cannotFindHost, .dnsLookupFailed, .cannotConnectToHost, .networkConnectionLost,
+        .timedOut,
+    ]
+    var attempt = 1
+    while true {
+        do {
+            return try await session.data(for: request)
+        } catch let error as URLError
+            where attempt < maxAttempts && transientCodes.contains(error.code)
+        {
+            let host = request.url?.host ?? "request"
+            print("[Networking] \(host) failed with \(error.code.rawValue), retrying")
+            try await Task.sleep(nanoseconds: UInt64(attempt) * 500_000_000)
+            attempt += 1
+        }
+    }
+}
+
+/// Client-side hard cap on streamed output, enforced even when a provider
+/// ignores the `max_tokens` it was sent.
+struct OutputTokenBudget {
+    /// Size ceiling for any single response, so a runaway stream can't exhaust memory.
+    static let maxCharacters = 4_000_000
+    /// Slack over the requested limit. Providers enforce max tokens themselves and
+    /// the character estimate is rough, so this only catches servers that ignore it.
+    static let headroom = 1.25
+
+    let limit: Int?
+    private var characterCount = 0
+
+    init(limit: Int?) {
+        self.limit = limit
+    }
+
+    /// Record streamed text or tool-call arguments. Returns the reason once the
+    /// estimated output clearly exceeds the limit or grows past `maxCharacters`.
+    mutating func consume(_ text: String) -> StreamTruncation? {
+        characterCount += text.count
+        if characterCount > Self.maxCharacters { return .responseSize }
+        guard let limit,
+            Double(characterCount) / TokenEstimator.charsPerToken > Double(limit) * Self.headroom
+        else { return nil }
+        return .outputTokenLimit(limit)
     }
 }
 
@@ -25,6 +167,7 @@ func validateHTTPResponse(_ response: URLResponse, data: Data) throws {
 func streamSSE(
     bytes: URLSession.AsyncBytes,
     response: URLResponse,
+    outputTokenLimit: Int? = nil,
     onEvent: @escaping @Sendable (StreamEvent) async -> Void
 ) async throws -> StreamResult {
     guard let http = response as? HTTPURLResponse else {
@@ -33,10 +176,8 @@ func streamSSE(
 
     if !(200...299).contains(http.statusCode) {
         let data = try await collectData(from: bytes)
-        if let apiError = try? JSONDecoder().decode(OpenAIErrorEnvelope.self, from: data) {
-            throw AdapterError.api(message: apiError.error.message)
-        }
-        throw AdapterError.api(message: "Request failed with status \(http.statusCode).")
+        let apiError = try? JSONDecoder().decode(OpenAIErrorEnvelope.self, from: data)
+        throw AdapterError.classify(status: http.statusCode, message: apiError?.error.message)
     }
 
     var emittedAny = false
@@ -46,17 +187,28 @@ func streamSSE(
     var toolCallAccumulators: [Int: (id: String, name: String, arguments: String)] = [:]
     // Track usage from the final chunk (if present)
     var finalUsage: TokenUsage?
-
-    for try await line in bytes.lines {
+    // Track url_citation annotations from native web search
+    var citations: [WebCitation] = []
+    var outputBudget = OutputTokenBudget(limit: outputTokenLimit)
+    var truncation: StreamTruncation?
+    var systemFingerprint: String?
+    // Refusal text streamed in place of content, or a content-filter stop
+    var refusalText = ""
+    var isContentFiltered = false
+
+    streamLoop: for try await sseEvent in ServerSentEvents(bytes: bytes) {
         try Task.checkCancellation()
 
-        guard line.hasPrefix("data:") else { continue }
-        let payload = String(line.dropFirst(5)).trimmingCharacters(in: .whitespaces)
+        let payload = sseEvent.data.trimmingCharacters(in: .whitespaces)
 
         if payload == "[DONE]" { break }
         guard let data = payload.data(using: .utf8) else { continue }
 
         if let chunk = try? decoder.decode(OpenAIChatStreamChunk.self, from: data) {
+            if let fingerprint = chunk.systemFingerprint {
+                systemFingerprint = fingerprint
+            }
+
             // Check for usage information (typically in final chunk)
             if let usage = chunk.usage {
                 finalUsage = TokenUsage(
@@ -72,6 +224,25 @@ func streamSSE(
                     emittedAny = true
                     fullText += text
                     await onEvent(.textDelta(text))
+                    if let reason = outputBudget.consume(text) {
+                        truncation = reason
+                        bytes.task.cancel()
+                        break streamLoop
+                    }
+                }
+
+                if let refusal = choice.delta.refusal {
+                    refusalText += refusal
+                }
+                if choice.finishReason == "content_filter" {
+                    isContentFiltered = true
+                }
+
+                // Handle web search citations
+                for annotation in choice.delta.annotations ?? [] {
+                    if let citation = annotation.urlCitation {
+                        citations.append(WebCitation(url: citation.url, title: citation.title))
+                    }
                 }
 
                 // Handle tool calls
@@ -86,6 +257,11 @@ func streamSSE(
                         if let argDelta = tc.function?.arguments, !argDelta.isEmpty {
                             toolCallAccumulators[idx]?.arguments += argDelta
                             await onEvent(.toolCallArgumentDelta(index: idx, delta: argDelta))
+                            if let reason = outputBudget.consume(argDelta) {
+                                truncation = reason
+                                bytes.task.cancel()
+                                break streamLoop
+                            }
                         }
                     }
                     emittedAny = true
@@ -95,27 +271,81 @@ func streamSSE(
         }
 
         if let apiError = try? decoder.decode(OpenAIErrorEnvelope.self, from: data) {
-            throw AdapterError.api(message: apiError.error.message)
+            throw AdapterError.classify(status: nil, message: apiError.error.message)
         }
     }
 
+    let refusal: String? =
+        !refusalText.isEmpty
+        ? refusalText
+        : isContentFiltered ? "The response was blocked by the provider's content filter." : nil
+    if let refusal {
+        await appendRefusalNotice(refusal, to: &fullText, onEvent: onEvent)
+        emittedAny = true
+    }
+
     if !emittedAny {
         throw AdapterError.missingResponseText
     }
 
-    // Build tool calls from accumulators
-    let toolCalls = toolCallAccumulators.sorted(by: { $0.key < $1.key }).map { (_, acc) in
-        ToolCallInfo(id: acc.id, name: acc.name, arguments: acc.arguments, serverName: "")
-    }
+    // Build tool calls from accumulators (dropped on truncation, their arguments may be partial)
+    let toolCalls =
+        truncation != nil
+        ? []
+        : toolCallAccumulators.sorted(by: { $0.key < $1.key }).map { (_, acc) in
+            ToolCallInfo(id: acc.id, name: acc.name, arguments: acc.arguments, serverName: "")
+        }
 
+    await appendCitationSources(citations, to: &fullText, onEvent: onEvent)
     await onEvent(.done)
-    return StreamResult(text: fullText, toolCalls: toolCalls, usage: finalUsage)
+    return StreamResult(
+        text: fullText, toolCalls: toolCalls, usage: finalUsage, citations: citations,
+        truncation: truncation, systemFingerprint: systemFingerprint, refusal: refusal)
+}
+
+/// Append a normalized markdown "Sources" list for citations returned by
+/// provider-native web search, so every provider renders them the same way.
+func appendCitationSources(
+    _ citations: [WebCitation],
+    to fullText: inout String,
+    onEvent: @escaping @Sendable (StreamEvent) async -> Void
+) async {
+    var seenURLs = Set<String>()
+    let unique = citations.filter { seenURLs.insert($0.url).inserted }
+    guard !unique.isEmpty else { return }
+
+    var lines = ["", "", "**Sources**"]
+    for (offset, citation) in unique.enumerated() {
+        let title = (citation.title ?? "").trimmingCharacters(in: .whitespacesAndNewlines)
+        lines.append("\(offset + 1). [\(title.isEmpty ? citation.url : title)](\(citation.url))")
+    }
+    let footer = lines.joined(separator: "\n")
+    fullText += footer
+    await onEvent(.textDelta(footer))
 }
 
-func collectData(from bytes: URLSession.AsyncBytes) async throws -> Data {
+/// Show a provider refusal or safety block as message text, so it isn't
+/// mistaken for an empty response.
+func appendRefusalNotice(
+    _ refusal: String,
+    to fullText: inout String,
+    onEvent: @escaping @Sendable (StreamEvent) async -> Void
+) async {
+    let notice = fullText.isEmpty ? refusal : "\n\n\(refusal)"
+    fullText += notice
+    await onEvent(.textDelta(notice))
+}
+
+/// Read an error response body, keeping at most `limit` bytes. An error envelope is
+/// small, so the rest of an oversized body is dropped and its transfer cancelled.
+func collectData(from bytes: URLSession.AsyncBytes, limit: Int = 16 * 1024) async throws -> Data {
     var data = Data()
     for try await byte in bytes {
         data.append(byte)
+        if data.count >= limit {
+            bytes.task.cancel()
+            break
+        }
     }
     return data
 }
@@ -281,12 +511,52 @@ struct OpenAIChatStreamRequest: Encodable {
     let stream: Bool
     let messages: [Message]
     let tools: [[String: AnyCodable]]?
+    /// OpenAI native web search (only accepted by search-capable models).
+    let webSearchOptions: [String: AnyCodable]?
+    /// OpenRouter plugins, e.g. `[{"id": "web"}]` for web search.
+    let plugins: [[String: AnyCodable]]?
+    let temperature: Double?
+    let maxTokens: Int?
+    /// OpenAI's replacement for `max_tokens`, required by reasoning models.
+    let maxCompletionTokens: Int?
+    /// Best-effort deterministic sampling; echoed back as `system_fingerprint`.
+    let seed: Int?
+    let streamOptions: StreamOptions?
+
+    /// Asks the provider to send a trailing chunk with token usage before `[DONE]`.
+    struct StreamOptions: Encodable {
+        let includeUsage: Bool
 
-    init(model: String, stream: Bool, messages: [Message], tools: [[String: AnyCodable]]? = nil) {
+        enum CodingKeys: String, CodingKey {
+            case includeUsage = "include_usage"
+        }
+    }
+
+    enum CodingKeys: String, CodingKey {
+        case model, stream, messages, tools, plugins, temperature, seed
+        case webSearchOptions = "web_search_options"
+        case maxTokens = "max_tokens"
+        case maxCompletionTokens = "max_completion_tokens"
+        case streamOptions = "stream_options"
+    }
+
+    init(
+        model: String, stream: Bool, messages: [Message], tools: [[String: AnyCodable]]? = nil,
+        webSearchOptions: [String: AnyCodable]? = nil, plugins: [[String: AnyCodable]]? = nil,
+        temperature: Double? = nil, maxTokens: Int? = nil, maxCompletionTokens: Int? = nil,
+        seed: Int? = nil, streamOptions: StreamOptions? = nil
+    ) {
         self.model = model
         self.stream = stream
         self.messages = messages
         self.tools = tools
+        self.webSearchOptions = webSearchOptions
+        self.plugins = plugins
+        self.temperature = temperature
+        self.maxTokens = maxTokens
+        self.maxCompletionTokens = maxCompletionTokens
+        self.seed = seed
+        self.streamOptions = streamOptions
     }
 }
 
@@ -309,12 +579,30 @@ struct OpenAIChatStreamChunk: Decodable {
                 }
             }
 
+            struct Annotation: Decodable {
+                struct URLCitation: Decodable {
+                    let url: String
+                    let title: String?
+                }
+
+                let type: String
+                let urlCitation: URLCitation?
+
+                enum CodingKeys: String, CodingKey {
+                    case type
+                    case urlCitation = "url_citation"
+                }
+            }
+
             let content: String?
             let contentParts: [ContentPart]?
             let toolCalls: [ToolCall]?
+            let annotations: [Annotation]?
+            /// Streamed refusal message, sent instead of `content`.
+            let refusal: String?
 
             enum CodingKeys: String, CodingKey {
-                case content
+                case content, annotations, refusal
                 case toolCalls = "tool_calls"
             }
 
@@ -336,6 +624,9 @@ struct OpenAIChatStreamChunk: Decodable {
                 }
 
                 toolCalls = try container.decodeIfPresent([ToolCall].self, forKey: .toolCalls)
+                annotations = try? container.decodeIfPresent(
+                    [Annotation].self, forKey: .annotations)
+                refusal = try? container.decodeIfPresent(String.self, forKey: .refusal)
             }
 
             var contentText: String? {
@@ -346,6 +637,12 @@ struct OpenAIChatStreamChunk: Decodable {
         }
 
         let delta: Delta
+        let finishReason: String?
+
+        enum CodingKeys: String, CodingKey {
+            case delta
+            case finishReason = "finish_reason"
+        }
     }
     
     /// Token usage information (only present in the final chunk of the stream)
@@ -363,6 +660,13 @@ struct OpenAIChatStreamChunk: Decodable {
 
     let choices: [Choice]
     let usage: Usage?
+    /// Backend configuration identifier; changes mean seeded output may differ.
+    let systemFingerprint: String?
+
+    enum CodingKeys: String, CodingKey {
+        case choices, usage
+        case systemFingerprint = "system_fingerprint"
+    }
 }
 
 struct OpenAIErrorEnvelope: Decodable {
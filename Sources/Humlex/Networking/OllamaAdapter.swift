@@ -8,6 +8,7 @@ struct OllamaAdapter: LLMProviderAdapter {
     func fetchModels(apiKey: String) async throws -> [LLMModel] {
         var request = URLRequest(url: URL(string: "\(baseURL)/api/tags")!)
         request.httpMethod = "GET"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
 
         let (data, response) = try await URLSession.shared.data(for: request)
         try validateOllamaHTTPResponse(response, data: data)
@@ -25,10 +26,13 @@ struct OllamaAdapter: LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult {
         var request = URLRequest(url: URL(string: "\(baseURL)/v1/chat/completions")!)
         request.httpMethod = "POST"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
+        request.timeoutInterval = StreamStallTimeout.seconds
         request.setValue("application/json", forHTTPHeaderField: "Content-Type")
 
         let toolDefs = ollamaToolDefs(from: tools)
@@ -36,12 +40,17 @@ struct OllamaAdapter: LLMProviderAdapter {
             model: modelID,
             stream: true,
             messages: history.map { apiMessage(from: $0) },
-            tools: toolDefs
+            tools: toolDefs,
+            temperature: options.temperature,
+            maxTokens: options.maxTokens,
+            seed: options.seed
         )
         request.httpBody = try JSONEncoder().encode(body)
 
         let (bytes, response) = try await URLSession.shared.bytes(for: request)
-        return try await streamSSE(bytes: bytes, response: response, onEvent: onEvent)
+        return try await streamSSE(
+            bytes: bytes, response: response, outputTokenLimit: options.maxTokens,
+            onEvent: onEvent)
     }
 }
 
@@ -64,11 +73,8 @@ private func validateOllamaHTTPResponse(_ response: URLResponse, data: Data) thr
         throw AdapterError.invalidResponse
     }
     guard (200...299).contains(http.statusCode) else {
-        if let envelope = try? JSONDecoder().decode(OllamaErrorEnvelope.self, from: data),
-           !envelope.error.isEmpty {
-            throw AdapterError.api(message: envelope.error)
-        }
-        throw AdapterError.api(message: "Request failed with status \(http.statusCode).")
+        let envelope = try? JSONDecoder().decode(OllamaErrorEnvelope.self, from: data)
+        throw AdapterError.classify(status: http.statusCode, message: envelope?.error)
     }
 }
 
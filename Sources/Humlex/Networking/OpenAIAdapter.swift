@@ -6,9 +6,10 @@ struct OpenAIAdapter: LLMProviderAdapter {
     func fetchModels(apiKey: String) async throws -> [LLMModel] {
         var request = URLRequest(url: URL(string: "https://api.openai.com/v1/models")!)
         request.httpMethod = "GET"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
         request.setValue("Bearer \(apiKey)", forHTTPHeaderField: "Authorization")
 
-        let (data, response) = try await URLSession.shared.data(for: request)
+        let (data, response) = try await dataWithRetry(for: request)
         try validateHTTPResponse(response, data: data)
 
         let decoded = try JSONDecoder().decode(OpenAIModelsResponse.self, from: data)
@@ -24,24 +25,37 @@ struct OpenAIAdapter: LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult {
         var request = URLRequest(url: URL(string: "https://api.openai.com/v1/chat/completions")!)
         request.httpMethod = "POST"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
+        request.timeoutInterval = StreamStallTimeout.seconds
         request.setValue("Bearer \(apiKey)", forHTTPHeaderField: "Authorization")
         request.setValue("application/json", forHTTPHeaderField: "Content-Type")
 
         let toolDefs = openAIToolDefs(from: tools)
+        // Chat completions only accept web_search_options on the search models
+        // (e.g. gpt-4o-search-preview); other models reject the field.
+        let usesWebSearch = options.isWebSearchEnabled && modelID.contains("search")
         let body = OpenAIChatStreamRequest(
             model: modelID,
             stream: true,
             messages: history.map { apiMessage(from: $0) },
-            tools: toolDefs
+            tools: toolDefs,
+            webSearchOptions: usesWebSearch ? [:] : nil,
+            temperature: options.temperature,
+            maxCompletionTokens: options.maxTokens,
+            seed: options.seed,
+            streamOptions: .init(includeUsage: true)
         )
         request.httpBody = try JSONEncoder().encode(body)
 
         let (bytes, response) = try await URLSession.shared.bytes(for: request)
-        return try await streamSSE(bytes: bytes, response: response, onEvent: onEvent)
+        return try await streamSSE(
+            bytes: bytes, response: response, outputTokenLimit: options.maxTokens,
+            onEvent: onEvent)
     }
 }
 
@@ -3,18 +3,34 @@ import Foundation
 struct OpenAICompatibleAdapter: LLMProviderAdapter {
     let provider: AIProvider = .openAICompatible
     let baseURLString: String
+    /// Extra endpoints serving the same models (e.g. other regions), tried after the
+    /// main one fails with a transport error or a 5xx status.
+    let fallbackBaseURLStrings: [String]
+    let tlsSettings: TLSSettings?
 
-    init(baseURLString: String) {
+    init(
+        baseURLString: String, fallbackBaseURLStrings: [String] = [],
+        tlsSettings: TLSSettings? = nil
+    ) {
         self.baseURLString = baseURLString
+        self.fallbackBaseURLStrings = fallbackBaseURLStrings
+        self.tlsSettings = tlsSettings
     }
 
     func fetchModels(apiKey: String) async throws -> [LLMModel] {
-        var request = URLRequest(url: try endpointURL(path: "models"))
-        request.httpMethod = "GET"
-        request.setValue(
-            "Bearer \(normalizedBearerToken(apiKey))", forHTTPHeaderField: "Authorization")
+        let session = makeSession()
+        defer { invalidateIfOwned(session) }
 
-        let (data, response) = try await URLSession.shared.data(for: request)
+        let (data, response) = try await withEndpointFailover(
+            stickyKey: nil, isIdempotent: true, discard: { $0 }
+        ) { baseURL in
+            var request = URLRequest(url: try endpointURL(base: baseURL, path: "models"))
+            request.httpMethod = "GET"
+            request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
+            request.setValue(
+                "Bearer \(normalizedBearerToken(apiKey))", forHTTPHeaderField: "Authorization")
+            return try await dataWithRetry(for: request, session: session)
+        }
         try validateHTTPResponse(response, data: data)
 
         let decoded = try JSONDecoder().decode(OpenAIModelsResponse.self, from: data)
@@ -30,29 +46,131 @@ struct OpenAICompatibleAdapter: LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult {
-        var request = URLRequest(url: try endpointURL(path: "chat/completions"))
-        request.httpMethod = "POST"
-        request.setValue(
-            "Bearer \(normalizedBearerToken(apiKey))", forHTTPHeaderField: "Authorization")
-        request.setValue("application/json", forHTTPHeaderField: "Content-Type")
-
         let toolDefs = openAICompatibleToolDefs(from: tools)
         let body = OpenAIChatStreamRequest(
             model: modelID,
             stream: true,
             messages: history.map { apiMessage(from: $0) },
-            tools: toolDefs
+            tools: toolDefs,
+            temperature: options.temperature,
+            maxTokens: options.maxTokens,
+            seed: options.seed
         )
-        request.httpBody = try JSONEncoder().encode(body)
+        let bodyData = try JSONEncoder().encode(body)
+
+        let session = makeSession()
+        defer { invalidateIfOwned(session) }
+
+        let (bytes, response) = try await withEndpointFailover(
+            stickyKey: options.conversationID,
+            isIdempotent: false,
+            discard: { bytes in
+                defer { bytes.task.cancel() }
+                return (try? await collectData(from: bytes, limit: 4096)) ?? Data()
+            }
+        ) { baseURL in
+            var request = URLRequest(url: try endpointURL(base: baseURL, path: "chat/completions"))
+            request.httpMethod = "POST"
+            request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
+            request.timeoutInterval = StreamStallTimeout.seconds
+            request.setValue(
+                "Bearer \(normalizedBearerToken(apiKey))", forHTTPHeaderField: "Authorization")
+            request.setValue("application/json", forHTTPHeaderField: "Content-Type")
+            request.httpBody = bodyData
+            return try await session.bytes(for: request)
+        }
+        return try await streamSSE(
+            bytes: bytes, response: response, outputTokenLimit: options.maxTokens,
+            onEvent: onEvent)
+    }
+
+    // MARK: - Endpoint Failover
+
+    /// Endpoints in the order they should be tried. The main endpoint always comes
+    /// first. With a sticky key (e.g. the conversation ID) the fallbacks are rotated to
+    /// a consistent starting point, so a conversation that has failed over keeps
+    /// hitting the same server and benefits from its prompt cache.
+    private func endpointCandidates(stickyKey: String?) -> [String] {
+        let fallbacks = fallbackBaseURLStrings.filter {
+            !$0.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty
+        }
+        guard let stickyKey, fallbacks.count > 1 else { return [baseURLString] + fallbacks }
+
+        let start = Int(stableHash(stickyKey) % UInt64(fallbacks.count))
+        return [baseURLString] + Array(fallbacks[start...] + fallbacks[..<start])
+    }
 
-        let (bytes, response) = try await URLSession.shared.bytes(for: request)
-        return try await streamSSE(bytes: bytes, response: response, onEvent: onEvent)
+    /// FNV-1a, which unlike `Hasher` is stable across app launches.
+    private func stableHash(_ string: String) -> UInt64 {
+        var hash: UInt64 = 0xcbf2_9ce4_8422_2325
+        for byte in string.utf8 {
+            hash ^= UInt64(byte)
+            hash = hash &* 0x0000_0100_0000_01b3
+        }
+        return hash
+    }
+
+    /// Transport errors raised before the request reaches the server, so it can be
+    /// sent elsewhere without risking a second billed completion.
+    private static let connectionFailureCodes: Set<URLError.Code> = [
+        .cannotFindHost, .dnsLookupFailed, .cannotConnectToHost, .secureConnectionFailed,
+        .serverCertificateUntrusted, .serverCertificateHasBadDate,
+        .serverCertificateNotYetValid, .serverCertificateHasUnknownRoot,
+        .clientCertificateRejected, .clientCertificateRequired,
+    ]
+
+    /// Run `attempt` against each endpoint in turn. 5xx responses and transport errors
+    /// move on to the next endpoint; the last endpoint's outcome is returned as-is.
+    /// Non-idempotent requests only move on after connection failures, since a timeout
+    /// or dropped connection may come after the server started generating.
+    /// `discard` releases a skipped response and returns its body for logging.
+    private func withEndpointFailover<Payload>(
+        stickyKey: String?,
+        isIdempotent: Bool,
+        discard: (Payload) async -> Data,
+        _ attempt: (String) async throws -> (Payload, URLResponse)
+    ) async throws -> (Payload, URLResponse) {
+        let candidates = endpointCandidates(stickyKey: stickyKey)
+        for (offset, baseURL) in candidates.enumerated() {
+            let isLast = offset == candidates.count - 1
+            do {
+                let (result, response) = try await attempt(baseURL)
+                if !isLast, let http = response as? HTTPURLResponse,
+                    (500...599).contains(http.statusCode)
+                {
+                    let body = String(decoding: await discard(result), as: UTF8.self)
+                    print(
+                        "[OpenAICompatible] \(baseURL) returned \(http.statusCode), trying next endpoint: \(body.prefix(500))"
+                    )
+                    continue
+                }
+                return (result, response)
+            } catch let error as URLError
+                where !isLast && error.code != .cancelled
+                && (isIdempotent || Self.connectionFailureCodes.contains(error.code))
+            {
+                print("[OpenAICompatible] \(baseURL) failed with \(error.code.rawValue), trying next endpoint")
+                continue
+            }
+        }
+        throw AdapterError.invalidResponse
+    }
+
+    private func makeSession() -> URLSession {
+        tlsSettings?.makeSession() ?? .shared
+    }
+
+    private func invalidateIfOwned(_ session: URLSession) {
+        if session !== URLSession.shared {
+            session.finishTasksAndInvalidate()
+        }
     }
 
-    private func endpointURL(path: String) throws -> URL {
-        let trimmedBase = baseURLString.trimmingCharacters(in: .whitespacesAndNewlines)
+    private func endpointURL(base: String, path: String) throws -> URL {
+        let trimmedBase = base.trimmingCharacters(in: .whitespacesAndNewlines)
             .trimmingCharacters(in: CharacterSet(charactersIn: "/"))
         guard !trimmedBase.isEmpty else {
             throw AdapterError.api(message: "Missing OpenAI Compatible API endpoint.")
@@ -6,9 +6,10 @@ struct OpenRouterAdapter: LLMProviderAdapter {
     func fetchModels(apiKey: String) async throws -> [LLMModel] {
         var request = URLRequest(url: URL(string: "https://openrouter.ai/api/v1/models")!)
         request.httpMethod = "GET"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
         request.setValue("Bearer \(apiKey)", forHTTPHeaderField: "Authorization")
 
-        let (data, response) = try await URLSession.shared.data(for: request)
+        let (data, response) = try await dataWithRetry(for: request)
         try validateHTTPResponse(response, data: data)
 
         let decoded = try JSONDecoder().decode(OpenRouterModelsResponse.self, from: data)
@@ -24,26 +25,36 @@ struct OpenRouterAdapter: LLMProviderAdapter {
         modelID: String,
         apiKey: String,
         tools: [MCPTool],
+        options: LLMRequestOptions,
         onEvent: @escaping @Sendable (StreamEvent) async -> Void
     ) async throws -> StreamResult {
         var request = URLRequest(url: URL(string: "https://openrouter.ai/api/v1/chat/completions")!)
         request.httpMethod = "POST"
+        request.setValue(RequestAttribution.userAgent, forHTTPHeaderField: "User-Agent")
+        request.timeoutInterval = StreamStallTimeout.seconds
         request.setValue("Bearer \(apiKey)", forHTTPHeaderField: "Authorization")
         request.setValue("application/json", forHTTPHeaderField: "Content-Type")
-        request.setValue("https://localhost", forHTTPHeaderField: "HTTP-Referer")
-        request.setValue("Humlex", forHTTPHeaderField: "X-Title")
+        request.setValue(RequestAttribution.openRouterReferer, forHTTPHeaderField: "HTTP-Referer")
+        request.setValue(RequestAttribution.openRouterTitle, forHTTPHeaderField: "X-Title")
 
         let toolDefs = openRouterToolDefs(from: tools)
         let body = OpenAIChatStreamRequest(
             model: modelID,
             stream: true,
             messages: history.map { apiMessage(from: $0) },
-            tools: toolDefs
+            tools: toolDefs,
+            plugins: options.isWebSearchEnabled ? [["id": AnyCodable("web")]] : nil,
+            temperature: options.temperature,
+            maxTokens: options.maxTokens,
+            seed: options.seed,
+            streamOptions: .init(includeUsage: true)
         )
         request.httpBody = try JSONEncoder().encode(body)
 
         let (bytes, response) = try await URLSession.shared.bytes(for: request)
-        return try await streamSSE(bytes: bytes, response: response, onEvent: onEvent)
+        return try await streamSSE(
+            bytes: bytes, response: response, outputTokenLimit: options.maxTokens,
+            onEvent: onEvent)
     }
 }
 
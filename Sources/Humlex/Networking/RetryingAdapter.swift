@@ -0,0 +1,67 @@
+import Foundation
+
+/// Wraps an adapter and retries a message when it fails with an error matching the
+/// provider's configured retry patterns. Retries only happen while nothing has been
+/// streamed yet, so the user never sees a reply restart halfway through.
+struct RetryingAdapter: LLMProviderAdapter {
+    let base: any LLMProviderAdapter
+    /// Lowercased fragments matched against the error code and message.
+    let retryPatterns: [String]
+    var maxAttempts = 3
+
+    var provider: AIProvider { base.provider }
+
+    func fetchModels(apiKey: String) async throws -> [LLMModel] {
+        try await base.fetchModels(apiKey: apiKey)
+    }
+
+    func streamMessage(
+        history: [LLMChatMessage],
+        modelID: String,
+        apiKey: String,
+        tools: [MCPTool],
+        options: LLMRequestOptions,
+        onEvent: @escaping @Sendable (StreamEvent) async -> Void
+    ) async throws -> StreamResult {
+        var attempt = 1
+        while true {
+            let progress = StreamProgress()
+            do {
+                return try await base.streamMessage(
+                    history: history,
+                    modelID: modelID,
+                    apiKey: apiKey,
+                    tools: tools,
+                    options: options
+                ) { event in
+                    await progress.markEmitted()
+                    await onEvent(event)
+                }
+            } catch {
+                let hasEmitted = await progress.hasEmitted
+                guard attempt < maxAttempts, !hasEmitted, isRetryable(error) else { throw error }
+                print(
+                    "[Retry] \(provider.rawValue) attempt \(attempt) failed: \(error.localizedDescription)"
+                )
+                try await Task.sleep(nanoseconds: UInt64(attempt) * 1_000_000_000)
+                attempt += 1
+            }
+        }
+    }
+
+    private func isRetryable(_ error: Error) -> Bool {
+        guard !retryPatterns.isEmpty, !(error is CancellationError) else { return false }
+        let code = (error as? AdapterError)?.code ?? ""
+        let haystack = "\(code) \(error.localizedDescription)".lowercased()
+        return retryPatterns.contains { haystack.contains($0) }
+    }
+}
+
+/// Records whether a stream has delivered any events yet.
+private actor StreamProgress {
+    private(set) var hasEmitted = false
+
+    func markEmitted() {
+        hasEmitted = true
+    }
+}
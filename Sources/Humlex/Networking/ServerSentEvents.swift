@@ -0,0 +1,133 @@
+import Foundation
+
+// MARK: - Server-Sent Events
+
+/// One server-sent event, with its `data:` lines joined by newlines.
+struct SSEEvent {
+    let event: String?
+    let data: String
+}
+
+/// Incremental line parser following the EventSource spec: multi-line `data:`
+/// fields are joined, comment lines are skipped, and a leading BOM is ignored.
+struct SSEParser {
+    private var eventName: String?
+    private var dataLines: [String] = []
+    private var isAtStreamStart = true
+    /// Bytes of `data:` held for the event not yet terminated by a blank line.
+    private(set) var pendingDataBytes = 0
+
+    /// Feed one line without its terminator. Returns an event when a blank line completes one.
+    mutating func consume(line rawLine: String) -> SSEEvent? {
+        var line = rawLine
+        if isAtStreamStart {
+            isAtStreamStart = false
+            if line.hasPrefix("\u{FEFF}") { line.removeFirst() }
+        }
+
+        if line.isEmpty { return flush() }
+        if line.hasPrefix(":") { return nil }
+
+        let field: Substring
+        var value: Substring
+        if let colon = line.firstIndex(of: ":") {
+            field = line[..<colon]
+            value = line[line.index(after: colon)...]
+            if value.hasPrefix(" ") { value = value.dropFirst() }
+        } else {
+            field = line[...]
+            value = ""
+        }
+
+        switch field {
+        case "data":
+            dataLines.append(String(value))
+            pendingDataBytes += value.utf8.count
+        case "event":
+            eventName = String(value)
+        default:
+            // id and retry are unused by provider streams
+            break
+        }
+        return nil
+    }
+
+    /// Emit the pending event, if it has any data, and reset for the next one.
+    mutating func flush() -> SSEEvent? {
+        defer {
+            eventName = nil
+            dataLines = []
+            pendingDataBytes = 0
+        }
+        guard !dataLines.isEmpty else { return nil }
+        return SSEEvent(event: eventName, data: dataLines.joined(separator: "\n"))
+    }
+}
+
+/// The events in a streamed response body. Splits lines on LF, CR, or CRLF itself,
+/// because the blank lines that separate events are significant.
+struct ServerSentEvents: AsyncSequence {
+    typealias Element = SSEEvent
+
+    /// Longest line accepted before the stream is treated as malformed.
+    static let maxLineBytes = 8 * 1024 * 1024
+    /// Largest event accepted, across all its `data:` lines, before a blank line ends it.
+    static let maxEventBytes = 16 * 1024 * 1024
+
+    let bytes: URLSession.AsyncBytes
+
+    func makeAsyncIterator() -> AsyncIterator {
+        AsyncIterator(byteIterator: bytes.makeAsyncIterator())
+    }
+
+    struct AsyncIterator: AsyncIteratorProtocol {
+        var byteIterator: URLSession.AsyncBytes.AsyncIterator
+        private var parser = SSEParser()
+        private var lineBuffer: [UInt8] = []
+        private var previousWasCarriageReturn = false
+        private var isFinished = false
+
+        init(byteIterator: URLSession.AsyncBytes.AsyncIterator) {
+            self.byteIterator = byteIterator
+        }
+
+        mutating func next() async throws -> SSEEvent? {
+            while !isFinished {
+                guard let byte = try await byteIterator.next() else {
+                    // Be lenient with streams that end without a trailing blank line
+                    isFinished = true
+                    if !lineBuffer.isEmpty { _ = parser.consume(line: takeLine()) }
+                    return parser.flush()
+                }
+
+                if byte == UInt8(ascii: "\n") && previousWasCarriageReturn {
+                    previousWasCarriageReturn = false
+                    continue
+                }
+                previousWasCarriageReturn = byte == UInt8(ascii: "\r")
+
+                if byte == UInt8(ascii: "\n") || byte == UInt8(ascii: "\r") {
+                    if let event = parser.consume(line: takeLine()) {
+                        return event
+                    }
+                    guard parser.pendingDataBytes <= ServerSentEvents.maxEventBytes else {
+                        throw AdapterError.api(
+                            message: "The provider sent a stream event that is too large.")
+                    }
+                } else {
+                    guard lineBuffer.count < ServerSentEvents.maxLineBytes else {
+                        throw AdapterError.api(
+                            message: "The provider sent a stream line that is too long.")
+                    }
+                    lineBuffer.append(byte)
+                }
+            }
+            return nil
+        }
+
+        private mutating func takeLine() -> String {
+            defer { lineBuffer.removeAll(keepingCapacity: true) }
+            return String(decoding: lineBuffer, as: UTF8.self)
+        }
+    }
+}
@@ -0,0 +1,144 @@
+import Foundation
+import Security
+
+// MARK: - TLS Settings
+
+/// Extra trust roots and a client identity for endpoints behind private PKI
+/// (e.g. self-hosted inference servers that require mutual TLS).
+struct TLSSettings {
+    let anchorCertificates: [SecCertificate]
+    let clientIdentity: SecIdentity?
+
+    var isEmpty: Bool { anchorCertificates.isEmpty && clientIdentity == nil }
+
+    /// Load a PEM bundle or DER file of CA certificates and an optional PKCS#12 identity.
+    static func load(
+        caCertificatePath: String?,
+        clientCertificatePath: String?,
+        clientCertificatePassword: String?
+    ) throws -> TLSSettings {
+        var anchors: [SecCertificate] = []
+        if let path = nonEmptyPath(caCertificatePath) {
+            anchors = try loadCertificates(atPath: path)
+        }
+
+        var identity: SecIdentity?
+        if let path = nonEmptyPath(clientCertificatePath) {
+            identity = try loadIdentity(atPath: path, password: clientCertificatePassword ?? "")
+        }
+
+        return TLSSettings(anchorCertificates: anchors, clientIdentity: identity)
+    }
+
+    /// A session that applies these settings, or the shared session when there is nothing to apply.
+    /// Callers own non-shared sessions and should invalidate them when done.
+    func makeSession() -> URLSession {
+        guard !isEmpty else { return .shared }
+        return URLSession(
+            configuration: .default,
+            delegate: TLSSessionDelegate(settings: self),
+            delegateQueue: nil
+        )
+    }
+
+    private static func nonEmptyPath(_ path: String?) -> String? {
+        let trimmed = (path ?? "").trimmingCharacters(in: .whitespacesAndNewlines)
+        return trimmed.isEmpty ? nil : (trimmed as NSString).expandingTildeInPath
+    }
+
+    private static func loadCertificates(atPath path: String) throws -> [SecCertificate] {
+        guard let data = FileManager.default.contents(atPath: path) else {
+            throw AdapterError.api(message: "Could not read CA certificate file at \(path).")
+        }
+
+        let pem = String(decoding: data, as: UTF8.self)
+        var derBlocks: [Data] = []
+        if pem.contains("-----BEGIN CERTIFICATE-----") {
+            for block in pem.components(separatedBy: "-----BEGIN CERTIFICATE-----").dropFirst() {
+                guard let end = block.range(of: "-----END CERTIFICATE-----") else { continue }
+                let base64 = block[..<end.lowerBound]
+                    .components(separatedBy: .whitespacesAndNewlines)
+                    .joined()
+                if let der = Data(base64Encoded: base64) {
+                    derBlocks.append(der)
+                }
+            }
+        } else {
+            derBlocks = [data]
+        }
+
+        let certificates = derBlocks.compactMap { SecCertificateCreateWithData(nil, $0 as CFData) }
+        guard !certificates.isEmpty else {
+            throw AdapterError.api(message: "No valid certificates found in \(path).")
+        }
+        return certificates
+    }
+
+    private static func loadIdentity(atPath path: String, password: String) throws -> SecIdentity {
+        guard let data = FileManager.default.contents(atPath: path) else {
+            throw AdapterError.api(message: "Could not read client certificate file at \(path).")
+        }
+
+        let options = [kSecImportExportPassphrase as String: password]
+        var items: CFArray?
+        let status = SecPKCS12Import(data as CFData, options as CFDictionary, &items)
+        guard status == errSecSuccess,
+            let first = (items as? [[String: Any]])?.first,
+            let identityRef = first[kSecImportItemIdentity as String]
+        else {
+            throw AdapterError.api(
+                message:
+                    "Could not import client certificate at \(path) (status \(status)). Check the password."
+            )
+        }
+        return identityRef as! SecIdentity
+    }
+}
+
+// MARK: - Session Delegate
+
+/// Evaluates server trust against extra anchors and answers client certificate challenges.
+final class TLSSessionDelegate: NSObject, URLSessionDelegate {
+    private let settings: TLSSettings
+
+    init(settings: TLSSettings) {
+        self.settings = settings
+    }
+
+    func urlSession(
+        _ session: URLSession,
+        didReceive challenge: URLAuthenticationChallenge,
+        completionHandler: @escaping (URLSession.AuthChallengeDisposition, URLCredential?) -> Void
+    ) {
+        switch challenge.protectionSpace.authenticationMethod {
+        case NSURLAuthenticationMethodServerTrust:
+            guard !settings.anchorCertificates.isEmpty,
+                let trust = challenge.protectionSpace.serverTrust
+            else {
+                completionHandler(.performDefaultHandling, nil)
+                return
+            }
+            // Trust the custom roots in addition to the system ones.
+            SecTrustSetAnchorCertificates(trust, settings.anchorCertificates as CFArray)
+            SecTrustSetAnchorCertificatesOnly(trust, false)
+            if SecTrustEvaluateWithError(trust, nil) {
+                completionHandler(.useCredential, URLCredential(trust: trust))
+            } else {
+                completionHandler(.cancelAuthenticationChallenge, nil)
+            }
+
+        case NSURLAuthenticationMethodClientCertificate:
+            guard let identity = settings.clientIdentity else {
+                completionHandler(.performDefaultHandling, nil)
+                return
+            }
+            completionHandler(
+                .useCredential,
+                URLCredential(identity: identity, certificates: nil, persistence: .forSession)
+            )
+
+        default:
+            completionHandler(.performDefaultHandling, nil)
+        }
+    }
+}
@@ -0,0 +1,149 @@
+import Foundation
+import AppKit
+
+/// Checks image attachments against what the target provider accepts before sending.
+/// Images in an unsupported format, or over the size limit, are re-encoded (as PNG
+/// when they have transparency, otherwise JPEG) when that makes them acceptable;
+/// otherwise a readable error is thrown.
+enum ImageAttachmentValidator {
+    /// Prepared attachments by attachment ID and provider, so each image is decoded
+    /// and re-encoded once rather than on every request of a tool loop.
+    private static let cache = NSCache<NSString, PreparedAttachment>()
+
+    private final class PreparedAttachment {
+        let attachment: Attachment
+        init(_ attachment: Attachment) { self.attachment = attachment }
+    }
+
+    /// Image formats each provider documents as accepted. Nil means unknown, so
+    /// images are passed through unchanged.
+    static func acceptedMimeTypes(for provider: AIProvider) -> Set<String>? {
+        switch provider {
+        case .anthropic, .openAI:
+            return ["image/jpeg", "image/png", "image/gif", "image/webp"]
+        case .gemini:
+            return ["image/jpeg", "image/png", "image/webp", "image/heic", "image/heif"]
+        default:
+            return nil
+        }
+    }
+
+    /// Maximum decoded size of a single image.
+    static func maxImageBytes(for provider: AIProvider) -> Int? {
+        switch provider {
+        case .anthropic: return 5 * 1024 * 1024
+        case .openAI, .gemini: return 20 * 1024 * 1024
+        default: return nil
+        }
+    }
+
+    /// Returns the history with image attachments adjusted for the provider.
+    static func prepare(_ history: [LLMChatMessage], for provider: AIProvider) throws
+        -> [LLMChatMessage]
+    {
+        guard let accepted = acceptedMimeTypes(for: provider) else { return history }
+        let limit = maxImageBytes(for: provider) ?? .max
+
+        return try history.map { message in
+            guard message.attachments.contains(where: \.isImage) else { return message }
+            let attachments = try message.attachments.map { attachment in
+                guard attachment.isImage else { return attachment }
+                let key = "\(attachment.id.uuidString)-\(provider.rawValue)" as NSString
+                if let cached = cache.object(forKey: key) { return cached.attachment }
+                let prepared = try prepare(
+                    attachment, provider: provider, accepted: accepted, limit: limit)
+                cache.setObject(PreparedAttachment(prepared), forKey: key)
+                return prepared
+            }
+            return LLMChatMessage(
+                role: message.role,
+                content: message.content,
+                attachments: attachments,
+                toolCalls: message.toolCalls,
+                toolResult: message.toolResult
+            )
+        }
+    }
+
+    private static func prepare(
+        _ attachment: Attachment, provider: AIProvider, accepted: Set<String>, limit: Int
+    ) throws -> Attachment {
+        guard let data = Data(base64Encoded: attachment.content) else {
+            throw AdapterError.api(message: "\"\(attachment.fileName)\" is not valid image data.")
+        }
+
+        // Trust the bytes over the file extension
+        let mimeType = sniffMimeType(data) ?? attachment.mimeType
+        if accepted.contains(mimeType) && data.count <= limit {
+            guard mimeType != attachment.mimeType else { return attachment }
+            return Attachment(
+                id: attachment.id, fileName: attachment.fileName, mimeType: mimeType,
+                content: attachment.content, fileSize: attachment.fileSize)
+        }
+
+        if let (encoded, encodedType) = reencoded(data, accepted: accepted, limit: limit) {
+            return Attachment(
+                id: attachment.id, fileName: attachment.fileName, mimeType: encodedType,
+                content: encoded.base64EncodedString(), fileSize: encoded.count)
+        }
+
+        let size = ByteCountFormatter.string(fromByteCount: Int64(data.count), countStyle: .file)
+        throw AdapterError.api(
+            message:
+                "\"\(attachment.fileName)\" (\(mimeType), \(size)) can't be sent to \(provider.rawValue). Use a smaller PNG, JPEG, or WebP image."
+        )
+    }
+
+    private static func sniffMimeType(_ data: Data) -> String? {
+        let bytes = [UInt8](data.prefix(12))
+        guard bytes.count >= 12 else { return nil }
+        if bytes.starts(with: [0x89, 0x50, 0x4E, 0x47]) { return "image/png" }
+        if bytes.starts(with: [0xFF, 0xD8, 0xFF]) { return "image/jpeg" }
+        if bytes.starts(with: Array("GIF8".utf8)) { return "image/gif" }
+        if bytes.starts(with: Array("RIFF".utf8)) && Array(bytes[8..<12]) == Array("WEBP".utf8) {
+            return "image/webp"
+        }
+        return nil
+    }
+
+    /// Re-encode as PNG when the image has transparency and PNG fits, otherwise as
+    /// JPEG with transparent areas drawn onto white.
+    private static func reencoded(_ data: Data, accepted: Set<String>, limit: Int) -> (
+        Data, String
+    )? {
+        guard let bitmap = NSBitmapImageRep(data: data) else { return nil }
+        if bitmap.hasAlpha, accepted.contains("image/png"),
+            let png = bitmap.representation(using: .png, properties: [:]), png.count <= limit
+        {
+            return (png, "image/png")
+        }
+        guard accepted.contains("image/jpeg"),
+            let jpeg = flattened(bitmap).representation(
+                using: .jpeg, properties: [.compressionFactor: 0.85]),
+            jpeg.count <= limit
+        else { return nil }
+        return (jpeg, "image/jpeg")
+    }
+
+    /// Draw the image over a white background, since JPEG has no alpha channel and
+    /// transparent pixels would otherwise come out black.
+    private static func flattened(_ bitmap: NSBitmapImageRep) -> NSBitmapImageRep {
+        guard bitmap.hasAlpha,
+            let opaque = NSBitmapImageRep(
+                bitmapDataPlanes: nil, pixelsWide: bitmap.pixelsWide,
+                pixelsHigh: bitmap.pixelsHigh, bitsPerSample: 8, samplesPerPixel: 4,
+                hasAlpha: true, isPlanar: false, colorSpaceName: .deviceRGB,
+                bytesPerRow: 0, bitsPerPixel: 0),
+            let context = NSGraphicsContext(bitmapImageRep: opaque)
+        else { return bitmap }
+
+        let rect = NSRect(x: 0, y: 0, width: bitmap.pixelsWide, height: bitmap.pixelsHigh)
+        NSGraphicsContext.saveGraphicsState()
+        NSGraphicsContext.current = context
+        NSColor.white.setFill()
+        rect.fill()
+        bitmap.draw(in: rect)
+        NSGraphicsContext.restoreGraphicsState()
+        return opaque
+    }
+}
@@ -23,10 +23,14 @@ struct ContentView: View {
     }
 
     @AppStorage("selected_model_reference") private var selectedModelReference: String = ""
+    @AppStorage("default_model_reference") private var defaultModelReference: String = ""
     @AppStorage("selected_thread_id") private var selectedThreadIDRaw: String = ""
     @AppStorage("provider_ollama_enabled") private var isOllamaEnabled = true
     @AppStorage("auto_scroll_enabled") private var isAutoScrollEnabled = true
     @AppStorage("performance_mode_enabled") private var isPerformanceModeEnabled = true
+    @AppStorage("web_search_enabled") private var isWebSearchEnabled = false
+    @AppStorage(UnsupportedParameterPolicy.storageKey) private var unsupportedParameterPolicy:
+        UnsupportedParameterPolicy = .warn
     @AppStorage("default_system_instructions") private var defaultSystemInstructions: String = ""
     @AppStorage("pinned_thread_ids") private var pinnedThreadIDsRaw: String = ""
     @AppStorage("performance_visible_message_limit") private var performanceVisibleMessageLimit =
@@ -67,6 +71,7 @@ struct ContentView: View {
     @AppStorage("openai_compatible_base_url") private var legacyOpenAICompatibleBaseURL: String = ""
     @State private var openAICompatibleProfiles: [OpenAICompatibleProfile] = []
     @State private var openAICompatibleTokens: [String: String] = [:]
+    @State private var openAICompatibleCertificatePasswords: [String: String] = [:]
     @State private var anthropicAPIKey: String = ""
     @State private var openRouterAPIKey: String = ""
     @State private var fastRouterAPIKey: String = ""
@@ -162,6 +167,10 @@ struct ContentView: View {
     }
 
     private var preferredDefaultModelReference: String {
+        // A user-chosen default wins whenever that model is still available
+        if models.contains(where: { $0.reference == defaultModelReference }) {
+            return defaultModelReference
+        }
         guard let preferred = models.sorted(by: isPreferredDefaultModel(_:_:)).first else {
             return selectedModelReference
         }
@@ -532,6 +541,9 @@ struct ContentView: View {
             .onChange(of: openAICompatibleTokens) { _, _ in
                 syncOpenAICompatibleTokensToKeychain()
             }
+            .onChange(of: openAICompatibleCertificatePasswords) { _, _ in
+                syncOpenAICompatibleTokensToKeychain()
+            }
             .onChange(of: selectedThreadID) { _, newValue in
                 selectedThreadIDRaw = newValue?.uuidString ?? ""
                 if let id = newValue, messageRenderLimitByThread[id] == nil {
@@ -1179,6 +1191,7 @@ struct ContentView: View {
             openAIAPIKey: $openAIAPIKey,
             openAICompatibleProfiles: $openAICompatibleProfiles,
             openAICompatibleTokens: $openAICompatibleTokens,
+            openAICompatibleCertificatePasswords: $openAICompatibleCertificatePasswords,
             anthropicAPIKey: $anthropicAPIKey,
             openRouterAPIKey: $openRouterAPIKey,
             fastRouterAPIKey: $fastRouterAPIKey,
@@ -2186,12 +2199,19 @@ struct ContentView: View {
 
     private func loadOpenAICompatibleTokensFromKeychain() {
         var loaded: [String: String] = [:]
+        var passwords: [String: String] = [:]
         for profile in openAICompatibleProfiles {
             let maybeToken = try? KeychainStore.loadString(
                 for: openAICompatibleTokenKey(for: profile.id))
             if let token = maybeToken ?? nil, !token.isEmpty { loaded[profile.id] = token }
+            let maybePassword = try? KeychainStore.loadString(
+                for: profile.clientCertificatePasswordKey)
+            if let password = maybePassword ?? nil, !password.isEmpty {
+                passwords[profile.id] = password
+            }
         }
         openAICompatibleTokens = loaded
+        openAICompatibleCertificatePasswords = passwords
     }
 
     private func syncOpenAICompatibleTokensToKeychain() {
@@ -2211,10 +2231,22 @@ struct ContentView: View {
                 } else {
                     try KeychainStore.saveString(token, for: key)
                 }
+
+                // Passwords are kept verbatim, whitespace can be part of them
+                let password = openAICompatibleCertificatePasswords[profileID] ?? ""
+                let passwordKey = OpenAICompatibleProfile.clientCertificatePasswordKey(
+                    for: profileID)
+                if password.isEmpty {
+                    try KeychainStore.deleteValue(for: passwordKey)
+                } else {
+                    try KeychainStore.saveString(password, for: passwordKey)
+                }
             }
 
             for removedID in knownIDs.subtracting(currentIDs) {
                 try KeychainStore.deleteValue(for: openAICompatibleTokenKey(for: removedID))
+                try KeychainStore.deleteValue(
+                    for: OpenAICompatibleProfile.clientCertificatePasswordKey(for: removedID))
             }
 
             openAICompatibleKnownIDsCSV = currentIDs.sorted().joined(separator: ",")
@@ -2299,7 +2331,11 @@ struct ContentView: View {
                     guard !endpoint.isEmpty, !token.isEmpty else { continue }
 
                     do {
-                        let adapter = OpenAICompatibleAdapter(baseURLString: endpoint)
+                        let adapter = OpenAICompatibleAdapter(
+                            baseURLString: endpoint,
+                            fallbackBaseURLStrings: profile.fallbackBaseURLs ?? [],
+                            tlsSettings: try profile.loadTLSSettings()
+                        )
                         let providerModels = try await adapter.fetchModels(apiKey: token)
                         let prefixed = providerModels.map { model in
                             LLMModel(
@@ -2390,6 +2426,18 @@ struct ContentView: View {
         let threadID = threads[idx].id
         guard !text.isEmpty || !pendingAttachments.isEmpty else { return }
 
+        // Checked before the message is added, so a rejected draft stays editable
+        let resolvedModelID =
+            selectedModel.provider == .openAICompatible
+            ? openAICompatibleProfileContext(for: selectedModel)?.modelID : nil
+        if let rejection = requestPlan(
+            for: selectedModel, modelID: resolvedModelID ?? selectedModel.modelID,
+            threadID: threadID, isAgent: threads[idx].agentEnabled
+        ).rejection {
+            statusMessage = rejection
+            return
+        }
+
         let messageAttachments = pendingAttachments
         draft = ""
         pendingAttachments = []
@@ -2414,12 +2462,23 @@ struct ContentView: View {
                 statusMessage = "Missing OpenAI Compatible profile endpoint or bearer token."
                 return
             }
+            let tlsSettings: TLSSettings
+            do {
+                tlsSettings = try context.profile.loadTLSSettings()
+            } catch {
+                statusMessage = error.localizedDescription
+                return
+            }
             await performStreamingLoop(
                 threadID: threadID,
                 threadIndex: idx,
                 model: selectedModel,
                 apiKey: context.token,
-                adapterOverride: OpenAICompatibleAdapter(baseURLString: context.profile.baseURL),
+                adapterOverride: OpenAICompatibleAdapter(
+                    baseURLString: context.profile.baseURL,
+                    fallbackBaseURLStrings: context.profile.fallbackBaseURLs ?? [],
+                    tlsSettings: tlsSettings
+                ),
                 modelIDOverride: context.modelID
             )
         } else {
@@ -2508,6 +2567,83 @@ struct ContentView: View {
 
     // MARK: - Tool-Use Streaming Loop
 
+    /// MCP tools plus the built-in agent tools in agent mode, or just fetch otherwise.
+    /// Gemini can't combine Google Search with function tools, so fetch is left out
+    /// there when web search is on.
+    private func availableTools(isAgent: Bool, provider: AIProvider) -> [MCPTool] {
+        if isAgent {
+            return mcpManager.tools + AgentTools.definitions()
+        }
+        if provider == .gemini && isWebSearchEnabled {
+            return mcpManager.tools
+        }
+        return mcpManager.tools + AgentTools.fetchDefinitions()
+    }
+
+    /// What a request will send, worked out once so the pre-send check and the
+    /// streaming loop agree.
+    private struct RequestPlan {
+        let options: LLMRequestOptions
+        let tools: [MCPTool]
+        /// Settings that were adjusted or dropped, shown as status.
+        let warnings: [String]
+        /// Set when the unsupported parameter policy refuses to send the request.
+        let rejection: String?
+    }
+
+    private func requestPlan(
+        for model: LLMModel, modelID: String, threadID: UUID, isAgent: Bool
+    ) -> RequestPlan {
+        let preset = ProviderPreset.preset(for: model.provider)
+        var warnings: [String] = []
+        var temperature = preset?.temperature
+        if let requested = temperature {
+            let range = model.provider.temperatureRange
+            let clamped = min(max(requested, range.lowerBound), range.upperBound)
+            if clamped != requested {
+                warnings.append(
+                    "Temperature \(requested.formatted()) is outside \(model.provider.rawValue)'s range (\(range.lowerBound.formatted())–\(range.upperBound.formatted())); using \(clamped.formatted())."
+                )
+                temperature = clamped
+            }
+        }
+        let options = LLMRequestOptions(
+            isWebSearchEnabled: isWebSearchEnabled,
+            temperature: temperature,
+            maxTokens: preset?.maxTokens,
+            conversationID: threadID.uuidString,
+            seed: preset?.seed
+        )
+        let tools = availableTools(isAgent: isAgent, provider: model.provider)
+
+        let unsupported = options.unsupportedParameters(
+            for: model.provider, modelID: modelID, sendsFunctionTools: !tools.isEmpty)
+        var rejection: String?
+        if let message = unsupportedParameterMessage(unsupported, provider: model.provider) {
+            if unsupportedParameterPolicy == .reject {
+                rejection = message
+            } else {
+                warnings.append(message)
+            }
+        }
+        return RequestPlan(options: options, tools: tools, warnings: warnings, rejection: rejection)
+    }
+
+    /// Status text for options the provider can't honor, or nil when nothing should be shown.
+    private func unsupportedParameterMessage(_ names: [String], provider: AIProvider) -> String? {
+        guard !names.isEmpty else { return nil }
+        let list = names.joined(separator: ", ")
+        switch unsupportedParameterPolicy {
+        case .strip:
+            return nil
+        case .warn:
+            return "\(provider.rawValue) doesn't support \(list); ignoring it."
+        case .reject:
+            return
+                "Not sent: \(provider.rawValue) doesn't support \(list). Remove it or change the unsupported parameter setting."
+        }
+    }
+
     /// Performs the streaming loop: sends to LLM, handles tool calls, re-sends with results.
     /// Loops until the LLM produces a response with no tool calls.
     /// In agent mode: merges built-in tools, prepends system prompt, routes execution, max 25 iterations.
@@ -2525,13 +2661,19 @@ struct ContentView: View {
         let workDir = threads[idx].workingDirectory
         let maxToolIterations = isAgent ? 25 : 5
         var previousToolCallSignature: String? = nil
-
-        // Merge tools: MCP tools + built-in agent tools (if agent mode is on)
-        // Always include fetch tool for normal chat mode
-        let availableTools: [MCPTool] =
-            isAgent
-            ? mcpManager.tools + AgentTools.definitions()
-            : mcpManager.tools + AgentTools.fetchDefinitions()
+        let preset = ProviderPreset.preset(for: model.provider)
+        let plan = requestPlan(
+            for: model, modelID: modelIDOverride ?? model.modelID, threadID: threadID,
+            isAgent: isAgent)
+        if let rejection = plan.rejection {
+            statusMessage = rejection
+            return
+        }
+        if !plan.warnings.isEmpty {
+            statusMessage = plan.warnings.joined(separator: " ")
+        }
+        let requestOptions = plan.options
+        let availableTools = plan.tools
 
         for _ in 0..<maxToolIterations {
             // Build history from current thread messages
@@ -2580,7 +2722,11 @@ struct ContentView: View {
 
             systemPromptParts.append(assistantSafetyBaselinePrompt)
 
-            if let configuredPrompt = resolvedSystemPrompt(for: threads[currentIdx]) {
+            // Thread prompt, then provider preset, then the global default
+            let chatPrompt = customSystemPrompt(for: threads[currentIdx])
+            if let configuredPrompt = preset?.systemPrompt(mergingChatPrompt: chatPrompt)
+                ?? chatPrompt ?? resolvedDefaultSystemPrompt
+            {
                 systemPromptParts.append(configuredPrompt)
             }
 
@@ -2623,14 +2769,25 @@ struct ContentView: View {
             streamingMessageID = assistantID
 
             do {
-                let effectiveAdapter = adapterOverride ?? adapter(for: model.provider)
+                let effectiveAdapter = RetryingAdapter(
+                    base: adapterOverride ?? adapter(for: model.provider),
+                    retryPatterns: preset?.retryPatterns ?? []
+                )
                 let effectiveModelID = modelIDOverride ?? model.modelID
 
+                // Image re-encoding can be slow, so keep it off the main actor
+                let provider = model.provider
+                let preparedHistory = try await Task.detached(priority: .userInitiated) {
+                    [history] in
+                    try ImageAttachmentValidator.prepare(history, for: provider)
+                }.value
+
                 let result = try await effectiveAdapter.streamMessage(
-                    history: history,
+                    history: preparedHistory,
                     modelID: effectiveModelID,
                     apiKey: apiKey,
-                    tools: availableTools
+                    tools: availableTools,
+                    options: requestOptions
                 ) { event in
                     await MainActor.run {
                         switch event {
@@ -2679,6 +2836,34 @@ struct ContentView: View {
                     }
                 }
 
+                if let seed = requestOptions.seed,
+                    let threadIdx = threads.firstIndex(where: { $0.id == threadID }),
+                    let msgIdx = threads[threadIdx].messages.firstIndex(where: {
+                        $0.id == assistantID
+                    })
+                {
+                    threads[threadIdx].messages[msgIdx].seed = seed
+                    threads[threadIdx].messages[msgIdx].systemFingerprint =
+                        result.systemFingerprint
+                }
+
+                if let refusal = result.refusal {
+                    print("[Streaming] Provider refused: \(refusal)")
+                    statusMessage = "\(model.provider.rawValue) declined to answer this request."
+                }
+
+                if let truncation = result.truncation {
+                    let reason: String
+                    switch truncation {
+                    case .outputTokenLimit(let limit):
+                        reason = "the \(limit)-token output limit"
+                    case .responseSize:
+                        reason = "the maximum response size"
+                    }
+                    print("[Streaming] Stopped response at \(reason)")
+                    statusMessage = "Response stopped at \(reason)."
+                }
+
                 // Check if there are tool calls to execute
                 if !result.toolCalls.isEmpty {
                     // Update the assistant message with tool call info
@@ -2866,7 +3051,22 @@ struct ContentView: View {
                     return
                 }
 
-                let text = "Request failed: \(error.localizedDescription)"
+                var text = "Request failed: \(error.localizedDescription)"
+                if let urlError = error as? URLError, urlError.code == .timedOut {
+                    text =
+                        "Request failed: \(model.provider.rawValue) stopped sending data for \(Int(StreamStallTimeout.seconds)) seconds."
+                }
+                if let adapterError = error as? AdapterError {
+                    print("[Streaming] Request failed with code \(adapterError.code)")
+                    // Point at the provider credential, not some other key the user might suspect
+                    if case .authentication(let detail) = adapterError {
+                        text =
+                            "Request failed: \(model.provider.rawValue) rejected its API key. Update it in Settings → \(model.provider.rawValue)."
+                        if let detail, !detail.isEmpty {
+                            text += " (\(detail))"
+                        }
+                    }
+                }
                 if messageText(for: assistantID, in: threadID).trimmingCharacters(
                     in: .whitespacesAndNewlines
                 ).isEmpty {
@@ -3402,12 +3602,12 @@ struct ContentView: View {
         }
     }
 
-    private func resolvedSystemPrompt(for thread: ChatThread) -> String? {
+    /// The thread's own system prompt. New threads start with a copy of the global
+    /// default, which doesn't count, so provider presets can still take its place.
+    private func customSystemPrompt(for thread: ChatThread) -> String? {
         let threadPrompt = (thread.systemPrompt ?? "").trimmingCharacters(in: .whitespacesAndNewlines)
-        if !threadPrompt.isEmpty {
-            return threadPrompt
-        }
-        return resolvedDefaultSystemPrompt
+        guard !threadPrompt.isEmpty, threadPrompt != resolvedDefaultSystemPrompt else { return nil }
+        return threadPrompt
     }
     // MARK: - Dangerous Mode: Undo Tracking
 
@@ -69,7 +69,10 @@ struct MessageRow: View {
     }
 
     private var messageTimestampLabel: String {
-        Self.timestampFormatter.string(from: message.timestamp)
+        let time = Self.timestampFormatter.string(from: message.timestamp)
+        guard let seed = message.seed else { return time }
+        let fingerprint = message.systemFingerprint.map { " · \($0)" } ?? ""
+        return "\(time) · seed \(seed)\(fingerprint)"
     }
 
     var body: some View {
@@ -6,6 +6,9 @@ struct ModelPickerPopover: View {
     @Binding var searchText: String
     @Binding var isPresented: Bool
 
+    /// Model used for new chats and when a chat's model is no longer available.
+    @AppStorage("default_model_reference") private var defaultModelReference: String = ""
+
     @Environment(\.appTheme) private var theme
 
     private var filteredModels: [LLMModel] {
@@ -93,6 +96,7 @@ struct ModelPickerPopover: View {
 
     private func modelRow(_ model: LLMModel) -> some View {
         let isSelected = model.reference == selectedModelReference
+        let isDefault = model.reference == defaultModelReference
         return Button {
             selectedModelReference = model.reference
             isPresented = false
@@ -108,6 +112,12 @@ struct ModelPickerPopover: View {
                     .foregroundStyle(theme.textPrimary)
                     .lineLimit(1)
                 Spacer()
+                if isDefault {
+                    Image(systemName: "star.fill")
+                        .font(.system(size: 10))
+                        .foregroundStyle(theme.textTertiary)
+                        .help("Default model")
+                }
                 if isSelected {
                     Image(systemName: "checkmark")
                         .font(.system(size: 11, weight: .semibold))
@@ -124,5 +134,16 @@ struct ModelPickerPopover: View {
         }
         .buttonStyle(.plain)
         .padding(.horizontal, 4)
+        .contextMenu {
+            if isDefault {
+                Button("Clear Default Model") {
+                    defaultModelReference = ""
+                }
+            } else {
+                Button("Set as Default Model") {
+                    defaultModelReference = model.reference
+                }
+            }
+        }
     }
 }
@@ -29,6 +29,7 @@ struct SettingsView: View {
     @Binding var openAIAPIKey: String
     @Binding var openAICompatibleProfiles: [OpenAICompatibleProfile]
     @Binding var openAICompatibleTokens: [String: String]
+    @Binding var openAICompatibleCertificatePasswords: [String: String]
     @Binding var anthropicAPIKey: String
     @Binding var openRouterAPIKey: String
     @Binding var fastRouterAPIKey: String
@@ -59,11 +60,20 @@ struct SettingsView: View {
     @AppStorage("provider_ollama_enabled") private var isOllamaEnabled = true
     @AppStorage("auto_scroll_enabled") private var isAutoScrollEnabled = true
     @AppStorage("performance_mode_enabled") private var isPerformanceModeEnabled = true
+    @AppStorage("web_search_enabled") private var isWebSearchEnabled = false
+    @AppStorage(UnsupportedParameterPolicy.storageKey) private var unsupportedParameterPolicy:
+        UnsupportedParameterPolicy = .warn
+    @AppStorage(StreamStallTimeout.storageKey) private var streamStallTimeoutSeconds =
+        StreamStallTimeout.defaultSeconds
+    @AppStorage(RequestAttribution.userAgentKey) private var customUserAgent: String = ""
+    @AppStorage(RequestAttribution.openRouterRefererKey) private var openRouterReferer: String = ""
+    @AppStorage(RequestAttribution.openRouterTitleKey) private var openRouterTitle: String = ""
     @AppStorage("performance_visible_message_limit") private var performanceVisibleMessageLimit =
         250
     @AppStorage("debug_mode_enabled") private var isDebugModeEnabled = false
     @AppStorage("default_system_instructions") private var defaultSystemInstructions: String = ""
     @AppStorage("chat_font_size") private var chatFontSize = 13.0
+    @AppStorage(ProviderPreset.storageKey) private var providerPresetsJSON: String = ""
 
     // MCP add server form
     @State private var showAddServerForm = false
@@ -460,6 +470,12 @@ struct SettingsView: View {
                 } else {
                     apiKeyField
                 }
+
+                if selectedProvider == .openRouter {
+                    openRouterAttributionSection
+                }
+
+                providerPresetSection
             }
             .padding(.horizontal, 24)
 
@@ -539,6 +555,67 @@ struct SettingsView: View {
         }
     }
 
+    private var openRouterAttributionSection: some View {
+        VStack(alignment: .leading, spacing: 8) {
+            Text("App Attribution")
+                .font(.system(size: 12, weight: .medium))
+                .foregroundStyle(theme.textSecondary)
+
+            TextField("HTTP-Referer (https://localhost)", text: $openRouterReferer)
+                .textFieldStyle(.roundedBorder)
+            TextField("X-Title (Humlex)", text: $openRouterTitle)
+                .textFieldStyle(.roundedBorder)
+
+            Text("Sent to OpenRouter to identify this app on its rankings and rate limits.")
+                .font(.system(size: 12))
+                .foregroundStyle(theme.textSecondary)
+        }
+    }
+
+    private var providerPresetSection: some View {
+        VStack(alignment: .leading, spacing: 8) {
+            Text("Request Defaults")
+                .font(.system(size: 12, weight: .medium))
+                .foregroundStyle(theme.textSecondary)
+
+            HStack(spacing: 8) {
+                TextField("Temperature", value: presetBinding(\.temperature), format: .number)
+                    .textFieldStyle(.roundedBorder)
+                TextField("Max tokens", value: presetBinding(\.maxTokens), format: .number)
+                    .textFieldStyle(.roundedBorder)
+                TextField("Seed", value: presetBinding(\.seed), format: .number)
+                    .textFieldStyle(.roundedBorder)
+            }
+
+            TextField(
+                "System prompt for chats without their own",
+                text: presetBinding(\.systemPrompt),
+                axis: .vertical
+            )
+            .textFieldStyle(.roundedBorder)
+            .lineLimit(3...6)
+
+            TextField(
+                "Retry on errors containing (e.g. overloaded, provider_unavailable)",
+                text: presetBinding(\.retryErrorPatterns)
+            )
+            .textFieldStyle(.roundedBorder)
+
+            Toggle(
+                "Pin system prompt so chat instructions can't override it",
+                isOn: presetBinding(\.isSystemPromptPinned)
+            )
+            .font(.system(size: 12))
+            .toggleStyle(.checkbox)
+
+            Text(
+                "Applied to requests sent with this provider's key that don't set their own values."
+            )
+            .font(.system(size: 12))
+            .foregroundStyle(theme.textSecondary)
+        }
+    }
+
     private var openAICompatibleDetailView: some View {
         VStack(alignment: .leading, spacing: 16) {
             HStack {
@@ -579,6 +656,7 @@ struct SettingsView: View {
                         Spacer()
                         Button(role: .destructive) {
                             openAICompatibleTokens[profileID] = nil
+                            openAICompatibleCertificatePasswords[profileID] = nil
                             openAICompatibleProfiles.remove(at: idx)
                         } label: {
                             Image(systemName: "trash")
@@ -606,6 +684,25 @@ struct SettingsView: View {
                     )
                     .textFieldStyle(.roundedBorder)
 
+                    TextField(
+                        "Fallback endpoints, comma-separated (optional)",
+                        text: Binding(
+                            get: {
+                                (openAICompatibleProfiles[idx].fallbackBaseURLs ?? [])
+                                    .joined(separator: ", ")
+                            },
+                            set: { newValue in
+                                // Keep empty entries while typing; the adapter skips them.
+                                let urls = newValue.split(
+                                    separator: ",", omittingEmptySubsequences: false
+                                ).map { $0.trimmingCharacters(in: .whitespaces) }
+                                openAICompatibleProfiles[idx].fallbackBaseURLs =
+                                    urls.allSatisfy(\.isEmpty) ? nil : urls
+                            }
+                        )
+                    )
+                    .textFieldStyle(.roundedBorder)
+
                     SecureField(
                         "Bearer token",
                         text: Binding(
@@ -614,6 +711,41 @@ struct SettingsView: View {
                         )
                     )
                     .textFieldStyle(.roundedBorder)
+
+                    DisclosureGroup("TLS") {
+                        VStack(alignment: .leading, spacing: 8) {
+                            TextField(
+                                "CA certificate path (PEM or DER, optional)",
+                                text: Binding(
+                                    get: { openAICompatibleProfiles[idx].caCertificatePath ?? "" },
+                                    set: { openAICompatibleProfiles[idx].caCertificatePath = $0 }
+                                )
+                            )
+                            .textFieldStyle(.roundedBorder)
+
+                            TextField(
+                                "Client certificate path (.p12, optional)",
+                                text: Binding(
+                                    get: {
+                                        openAICompatibleProfiles[idx].clientCertificatePath ?? ""
+                                    },
+                                    set: { openAICompatibleProfiles[idx].clientCertificatePath = $0 }
+                                )
+                            )
+                            .textFieldStyle(.roundedBorder)
+
+                            SecureField(
+                                "Client certificate password",
+                                text: Binding(
+                                    get: { openAICompatibleCertificatePasswords[profileID] ?? "" },
+                                    set: { openAICompatibleCertificatePasswords[profileID] = $0 }
+                                )
+                            )
+                            .textFieldStyle(.roundedBorder)
+                        }
+                        .padding(.top, 6)
+                    }
+                    .font(.system(size: 12))
                 }
                 .padding(12)
                 .background(
@@ -626,7 +758,9 @@ struct SettingsView: View {
                 )
             }
 
-            Text("Supports OpenAI-style APIs. If `/v1` is omitted, it is added automatically.")
+            Text(
+                "Supports OpenAI-style APIs. If `/v1` is omitted, it is added automatically. When the main endpoint is unreachable or returns a server error, the fallbacks are tried; each conversation keeps to the same fallback."
+            )
                 .font(.system(size: 12))
                 .foregroundStyle(theme.textSecondary)
         }
@@ -1496,6 +1630,13 @@ struct SettingsView: View {
                             isOn: $isPerformanceModeEnabled,
                             showDivider: true
                         )
+                        settingsToggleRow(
+                            title: "Web search",
+                            subtitle:
+                                "Use native search on OpenAI search models, Anthropic, OpenRouter, and Gemini outside agent mode when no MCP tools are connected.",
+                            isOn: $isWebSearchEnabled,
+                            showDivider: true
+                        )
                         settingsToggleRow(
                             title: "Debug mode",
                             subtitle: "Show FPS, CPU, and memory metrics overlay.",
@@ -1504,6 +1645,61 @@ struct SettingsView: View {
                         )
                     }
 
+                    settingsGroup("Network") {
+                        VStack(alignment: .leading, spacing: 8) {
+                            Text("User-Agent")
+                                .font(.system(size: 15, weight: .medium))
+                                .foregroundStyle(theme.textPrimary)
+
+                            TextField(RequestAttribution.defaultUserAgent, text: $customUserAgent)
+                                .textFieldStyle(.roundedBorder)
+
+                            Text("Sent to every provider except Kimi for Coding. Leave empty for the default.")
+                                .font(.system(size: 11))
+                                .foregroundStyle(theme.textTertiary)
+
+                            Text("Stall timeout")
+                                .font(.system(size: 15, weight: .medium))
+                                .foregroundStyle(theme.textPrimary)
+                                .padding(.top, 6)
+
+                            Picker("Stall timeout", selection: $streamStallTimeoutSeconds) {
+                                Text("30s").tag(30.0)
+                                Text("60s").tag(60.0)
+                                Text("2m").tag(120.0)
+                                Text("5m").tag(300.0)
+                            }
+                            .pickerStyle(.segmented)
+                            .labelsHidden()
+
+                            Text("Abort a response when the provider sends nothing for this long.")
+                                .font(.system(size: 11))
+                                .foregroundStyle(theme.textTertiary)
+
+                            Text("Unsupported parameters")
+                                .font(.system(size: 15, weight: .medium))
+                                .foregroundStyle(theme.textPrimary)
+                                .padding(.top, 6)
+
+                            Picker("Unsupported parameters", selection: $unsupportedParameterPolicy)
+                            {
+                                ForEach(UnsupportedParameterPolicy.allCases) { policy in
+                                    Text(policy.title).tag(policy)
+                                }
+                            }
+                            .pickerStyle(.segmented)
+                            .labelsHidden()
+
+                            Text(
+                                "What to do when a seed or web search is set but the provider can't use it."
+                            )
+                            .font(.system(size: 11))
+                            .foregroundStyle(theme.textTertiary)
+                        }
+                        .padding(.horizontal, 14)
+                        .padding(.vertical, 12)
+                    }
+
                     settingsGroup("Performance") {
                         VStack(alignment: .leading, spacing: 10) {
                             HStack {
@@ -1720,6 +1916,25 @@ struct SettingsView: View {
 
     // MARK: - Helpers
 
+    private func presetBinding<Value>(_ keyPath: WritableKeyPath<ProviderPreset, Value>)
+        -> Binding<Value>
+    {
+        let providerKey = selectedProvider.rawValue
+        return Binding(
+            get: {
+                let presets = ProviderPreset.decodeAll(from: providerPresetsJSON)
+                return (presets[providerKey] ?? ProviderPreset())[keyPath: keyPath]
+            },
+            set: { newValue in
+                var presets = ProviderPreset.decodeAll(from: providerPresetsJSON)
+                var preset = presets[providerKey] ?? ProviderPreset()
+                preset[keyPath: keyPath] = newValue
+                presets[providerKey] = preset
+                providerPresetsJSON = ProviderPreset.encodeAll(presets)
+            }
+        )
+    }
+
     private func apiKeyBinding(for provider: AIProvider) -> Binding<String> {
         switch provider {
         case .openAI: return $openAIAPIKey